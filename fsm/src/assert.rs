@@ -0,0 +1,54 @@
+// Compile-time assertions on the transitions of a machine
+//
+// Both macros expand to trait-bound checks against the `Transition` impls
+// generated by `fsm!`, so changing the machine in a way that breaks them
+// fails to compile:
+//
+//     assert_transition!(door: Closed --Open--> Opened);
+//     assert_no_transition!(door: Locked --Open);
+//
+// The machine is the path of the module generated by `fsm!`.
+
+// Assert that the event moves the machine from the first state to the second
+//
+// Fails with an unsatisfied `Transition` bound otherwise.
+#[macro_export]
+macro_rules! assert_transition {
+    ($($machine:ident)::+ : $from:ident --$event:ident--> $to:ident) => {
+        const _: fn() = || {
+            fn transition<T>()
+            where
+                T: $crate::Transition<$($machine)::+::$event, SM = $($machine)::+::FSM<$($machine)::+::$to>>
+            { }
+
+            transition::<$($machine)::+::FSM<$($machine)::+::$from>>();
+        };
+    };
+}
+
+// Assert that the state has no transition for the event
+//
+// The check relies on an ambiguity: a helper trait has one impl for every
+// type and one for the types with the transition. Fails with "type
+// annotations needed" if the state has the transition.
+#[macro_export]
+macro_rules! assert_no_transition {
+    ($($machine:ident)::+ : $from:ident --$event:ident) => {
+        const _: fn() = || {
+            trait AmbiguousIfTransition<A> {
+                fn some_item() { }
+            }
+
+            impl<T: ?Sized> AmbiguousIfTransition<()> for T { }
+
+            struct HasTransition;
+
+            impl<T> AmbiguousIfTransition<HasTransition> for T
+            where
+                T: ?Sized + $crate::Transition<$($machine)::+::$event>
+            { }
+
+            let _ = <$($machine)::+::FSM<$($machine)::+::$from> as AmbiguousIfTransition<_>>::some_item;
+        };
+    };
+}
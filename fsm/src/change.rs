@@ -0,0 +1,47 @@
+use crate::Dispatch;
+
+// A state change made by a driver of the machine
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Change<I, E> {
+    // The event moved the machine from one state to the next
+    Moved {
+        from: I,
+        event: E,
+        to: I
+    },
+    // The current state has no transition for the event, the machine is unchanged
+    Rejected {
+        state: I,
+        event: E
+    }
+}
+
+impl<I: Copy, E: Copy> Change<I, E> {
+    // The event that caused the change
+    pub fn event(&self) -> E {
+        match self {
+            Change::Moved { event, .. } | Change::Rejected { event, .. } => *event
+        }
+    }
+
+    // The state of the machine after the change
+    pub fn state(&self) -> I {
+        match self {
+            Change::Moved { to, .. } => *to,
+            Change::Rejected { state, .. } => *state
+        }
+    }
+}
+
+// Dispatch `event` on `machine` and describe the change it made
+pub(crate) fn apply<M: Dispatch>(machine: M, event: M::Event) -> (M, Change<M::Id, M::Event>) {
+    let from = machine.id();
+
+    match machine.dispatch(event) {
+        Ok (machine) => {
+            let to = machine.id();
+            (machine, Change::Moved { from, event, to })
+        },
+        Err (machine) => (machine, Change::Rejected { state: from, event })
+    }
+}
@@ -0,0 +1,184 @@
+// Bounded model checking of the transition table of a machine
+//
+// `Model` explores a machine from every `Init` state, or from every state of
+// a machine without `Init` block, up to N events deep, and checks properties
+// on it. States are explored shortest walk first, so the counterexample
+// returned for a violated property is a shortest one:
+//
+//     let model = Model::<door::StateId>::new(8);
+//     model.always(|walk| walk.steps().all(|(from, event, _)| {
+//         from != door::StateId::Locked || event != door::Events::Open
+//     }))?;
+//     model.reaches(|state| state == door::StateId::Closed)?;
+//
+// `never` and `reaches` visit every state once. `always` checks a property
+// of whole walks and therefore enumerates every walk, whose number grows
+// exponentially with the depth, which should stay small enough for a test.
+
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::{
+    walk::{self, Walk},
+    Table
+};
+
+// The walks of a machine up to a depth
+#[derive(Clone, Copy, Debug)]
+pub struct Model<T: Table> {
+    depth: usize,
+    _t: PhantomData<T>
+}
+
+// A state reached by the search, with the state and event it was first
+// reached from
+struct Node<T: Table> {
+    state: T,
+    parent: Option<(usize, T::Event)>,
+    depth: usize
+}
+
+impl<T: Table> Model<T> {
+    // The walks of at most `depth` events from every `Init` state, or from
+    // every state of a machine without `Init` block
+    pub const fn new(depth: usize) -> Self {
+        Self {
+            depth,
+            _t: PhantomData
+        }
+    }
+
+    // The maximal number of events of a walk
+    pub const fn depth(&self) -> usize {
+        self.depth
+    }
+
+    // Every walk, shortest first, including the empty walk of every start
+    // state
+    pub fn walks(&self) -> impl Iterator<Item = Walk<T>> {
+        walks(walk::starts::<T>().iter().copied(), self.depth)
+    }
+
+    // Check that `property` holds for every walk, or return the shortest
+    // walk it fails for
+    pub fn always<F: FnMut(&Walk<T>) -> bool>(&self, mut property: F) -> Result<(), Walk<T>> {
+        match self.walks().find(|walk| !property(walk)) {
+            Some (walk) => Err(walk),
+            None => Ok (())
+        }
+    }
+
+    // Check that no walk ever enters a state matching `state`, or return
+    // the shortest walk that does
+    pub fn never<F: FnMut(T) -> bool>(&self, mut state: F) -> Result<(), Walk<T>> {
+        let nodes = self.explore();
+
+        match nodes.iter().position(|node| state(node.state)) {
+            Some (found) => Err(rebuild(&nodes, found)),
+            None => Ok (())
+        }
+    }
+
+    // Check that from every state a walk reaches, a state matching `target`
+    // is reachable within the depth as well
+    //
+    // Returns the shortest walk to a state from which no matching state can
+    // be reached.
+    pub fn reaches<F: FnMut(T) -> bool>(&self, mut target: F) -> Result<(), Walk<T>> {
+        // The states from which a matching state is at most `steps` events
+        // away, growing backwards from the matching states one event a round
+        let mut good: Vec<bool> = T::STATES.iter().map(|state| target(*state)).collect();
+
+        for _ in 0..self.depth {
+            let grown: Vec<bool> = T::STATES.iter()
+                .zip(good.iter())
+                .map(|(state, good_now)| *good_now || walk::legal(*state).any(|event| {
+                    state.next(event).is_some_and(|next| good[index(next)])
+                }))
+                .collect();
+
+            if grown == good {
+                break;
+            }
+
+            good = grown;
+        }
+
+        let nodes = self.explore();
+
+        match nodes.iter().position(|node| !good[index(node.state)]) {
+            Some (found) => Err(rebuild(&nodes, found)),
+            None => Ok (())
+        }
+    }
+
+    // Every state reachable within the depth, each once and in the order
+    // of the shortest walk to it
+    fn explore(&self) -> Vec<Node<T>> {
+        let mut seen = alloc::vec![false; T::STATES.len()];
+        let mut nodes: Vec<Node<T>> = Vec::new();
+
+        for start in walk::starts::<T>() {
+            if !core::mem::replace(&mut seen[index(*start)], true) {
+                nodes.push(Node { state: *start, parent: None, depth: 0 });
+            }
+        }
+
+        let mut current = 0;
+
+        while let Some(node) = nodes.get(current) {
+            let (state, depth) = (node.state, node.depth);
+
+            if depth < self.depth {
+                for event in walk::legal(state) {
+                    let next = state.next(event).expect("the event is legal in the state");
+
+                    if !core::mem::replace(&mut seen[index(next)], true) {
+                        nodes.push(Node { state: next, parent: Some ((current, event)), depth: depth + 1 });
+                    }
+                }
+            }
+
+            current += 1;
+        }
+
+        nodes
+    }
+}
+
+// The position of `state` in `T::STATES`
+fn index<T: Table>(state: T) -> usize {
+    T::STATES.iter().position(|known| *known == state).expect("every state is in STATES")
+}
+
+// The walk to `nodes[found]`, following the parents back to its start
+fn rebuild<T: Table>(nodes: &[Node<T>], found: usize) -> Walk<T> {
+    let mut events = Vec::new();
+    let mut current = found;
+
+    while let Some((parent, event)) = nodes[current].parent {
+        events.push(event);
+        current = parent;
+    }
+
+    events.reverse();
+
+    Walk { start: nodes[current].state, events }
+}
+
+// Every walk of at most `depth` events from `starts`, shortest first
+fn walks<T: Table>(starts: impl Iterator<Item = T>, depth: usize) -> impl Iterator<Item = Walk<T>> {
+    let mut queue: VecDeque<Walk<T>> = starts.map(|start| Walk { start, events: Vec::new() }).collect();
+
+    core::iter::from_fn(move || {
+        let walk = queue.pop_front()?;
+
+        if walk.events.len() < depth {
+            let end = walk.end();
+            queue.extend(walk::legal(end).filter_map(|event| walk.then(event)));
+        }
+
+        Some (walk)
+    })
+}
@@ -0,0 +1,157 @@
+// Transition coverage of test suites
+//
+// `Coverage` is an observer counting how often each declared transition of a
+// machine is taken, and reports the transitions that never were. It prints
+// as text, one line per missed transition:
+//
+//     door: 2 of 3 transitions covered
+//     missed: Closed --Kick--> Broken
+//
+// and `Coverage::json` as JSON, with the hits of every transition:
+//
+//     {"machine":"door","covered":2,"declared":3,"transitions":[
+//         {"from":"Closed","event":"Open","to":"Opened","hits":4}, ...]}
+//
+// The JSON is written on one line.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::{
+    observer::{Named, Observer},
+    Dispatch,
+    Table
+};
+
+// The number of times each transition of a machine was taken
+pub struct Coverage<M: Dispatch> {
+    hits: Vec<u64>,
+    _m: PhantomData<M>
+}
+
+impl<M: Dispatch> Coverage<M>
+where
+    M::Id: Table<Event = M::Event>
+{
+    // No transition taken yet
+    pub fn new() -> Self {
+        Self {
+            hits: vec![0; M::Id::STATES.len() * M::Id::EVENTS.len()],
+            _m: PhantomData
+        }
+    }
+
+    // The number of times `event` was dispatched in `state` and moved the
+    // machine
+    pub fn hits(&self, state: M::Id, event: M::Event) -> u64 {
+        index::<M>(state, event).map_or(0, |i| self.hits[i])
+    }
+
+    // Every declared transition as `(from, event, to)`, by state ID and event
+    // in declaration order
+    pub fn declared(&self) -> impl Iterator<Item = (M::Id, M::Event, M::Id)> {
+        M::Id::STATES.iter().flat_map(|state| {
+            M::Id::EVENTS.iter().filter_map(move |event| Some ((*state, *event, state.next(*event)?)))
+        })
+    }
+
+    // The declared transitions that were never taken
+    pub fn missed(&self) -> impl Iterator<Item = (M::Id, M::Event, M::Id)> + '_ {
+        self.declared().filter(|(state, event, _)| self.hits(*state, *event) == 0)
+    }
+
+    // The number of declared transitions taken at least once
+    pub fn covered(&self) -> usize {
+        self.declared().filter(|(state, event, _)| self.hits(*state, *event) > 0).count()
+    }
+
+    // Add the hits of `other`, e.g. of another test
+    pub fn merge(&mut self, other: &Self) {
+        self.hits.iter_mut().zip(&other.hits).for_each(|(hits, other)| *hits += other);
+    }
+
+    // Forget every taken transition
+    pub fn clear(&mut self) {
+        self.hits.iter_mut().for_each(|hits| *hits = 0);
+    }
+
+    // The coverage as JSON
+    pub fn json(&self) -> CoverageJson<'_, M> {
+        CoverageJson(self)
+    }
+}
+
+impl<M: Dispatch> Default for Coverage<M>
+where
+    M::Id: Table<Event = M::Event>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M: Dispatch> Observer<M> for Coverage<M>
+where
+    M::Id: Table<Event = M::Event>
+{
+    fn on_transition(&mut self, from: Named<M::Id>, event: Named<M::Event>, _to: Named<M::Id>) {
+        if let Some(i) = index::<M>(from.id, event.id) {
+            self.hits[i] += 1;
+        }
+    }
+}
+
+impl<M: Dispatch> fmt::Display for Coverage<M>
+where
+    M::Id: Table<Event = M::Event>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}: {} of {} transitions covered", M::NAME, self.covered(), self.declared().count())?;
+
+        for (from, event, to) in self.missed() {
+            writeln!(f, "missed: {} --{}--> {}", from.name(), M::Id::event_name(event), to.name())?;
+        }
+
+        Ok (())
+    }
+}
+
+// The coverage of a machine as JSON, see `Coverage::json`
+pub struct CoverageJson<'a, M: Dispatch>(&'a Coverage<M>);
+
+impl<M: Dispatch> fmt::Display for CoverageJson<'_, M>
+where
+    M::Id: Table<Event = M::Event>
+{
+    // The names are identifiers, which need no escaping
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let coverage = self.0;
+
+        write!(f, r#"{{"machine":"{}","covered":{},"declared":{},"transitions":["#,
+            M::NAME, coverage.covered(), coverage.declared().count())?;
+
+        for (i, (from, event, to)) in coverage.declared().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+
+            write!(f, r#"{{"from":"{}","event":"{}","to":"{}","hits":{}}}"#,
+                from.name(), M::Id::event_name(event), to.name(), coverage.hits(from, event))?;
+        }
+
+        write!(f, "]}}")
+    }
+}
+
+// The position of the counter of `event` in `state`
+fn index<M: Dispatch>(state: M::Id, event: M::Event) -> Option<usize>
+where
+    M::Id: Table<Event = M::Event>
+{
+    let state = M::Id::STATES.iter().position(|candidate| *candidate == state)?;
+    let event = M::Id::EVENTS.iter().position(|candidate| *candidate == event)?;
+
+    Some (state * M::Id::EVENTS.len() + event)
+}
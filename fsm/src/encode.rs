@@ -0,0 +1,189 @@
+// The binary snapshot format of `Variants`
+//
+// A snapshot is the version of the machine, the state ID and the memory of
+// the state:
+//
+//     | version | state ID | memory 0 | memory 1 | ...
+//
+// The version is only written for machines declaring `#[version(N)]`, as a
+// `u16` in little endian. Snapshots of the versions listed in the `Migrate`
+// blocks are decoded with the states, memory and state ID width of that
+// version. Snapshots without version header, written before the machine
+// declared one, are version 0 and read with `Variants::decode_unversioned`.
+//
+// The state ID is the `StateId` of the state in little endian, one byte wide
+// for `#[repr(u8)]` machines and two bytes wide otherwise. The memory values
+// follow in the order of the `Memory` block, each written by its `Encode`
+// implementation:
+//
+// - integers and floats in little endian, with their size in bytes
+// - `bool` as one byte, 0 or 1
+// - `char` as its `u32` scalar value
+// - `()` as nothing
+// - `[T; N]` as its N elements
+// - `Option<T>` as one byte, 0 for `None` or 1 for `Some` followed by the value
+
+use core::fmt;
+
+// An error raised while encoding or decoding a snapshot
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EncodeError {
+    // The buffer is too small to hold the encoded value
+    BufferFull,
+    // The buffer ends before the encoded value does
+    Truncated,
+    // The state ID does not belong to a state of the machine
+    UnknownState(u16),
+    // The snapshot was written by a version that cannot be migrated
+    UnknownVersion(u16),
+    // The bytes do not encode a valid value
+    Invalid
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodeError::BufferFull => write!(f, "buffer too small for the snapshot"),
+            EncodeError::Truncated => write!(f, "snapshot ends unexpectedly"),
+            EncodeError::UnknownState(id) => write!(f, "unknown state ID {}", id),
+            EncodeError::UnknownVersion(version) => write!(f, "unknown snapshot version {}", version),
+            EncodeError::Invalid => write!(f, "invalid value in the snapshot")
+        }
+    }
+}
+
+// A trait for values stored in a binary snapshot
+pub trait Encode: Sized {
+    // Write the value to the start of `buf` and return the number of bytes written
+    fn encode(&self, buf: &mut [u8]) -> Result<usize, EncodeError>;
+
+    // Read a value from the start of `buf` and return it with the number of bytes read
+    fn decode(buf: &[u8]) -> Result<(Self, usize), EncodeError>;
+}
+
+macro_rules! encode_le {
+    ($($ty:ty),*) => {
+        $(
+            impl Encode for $ty {
+                fn encode(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+                    let bytes = self.to_le_bytes();
+                    buf.get_mut(..bytes.len())
+                        .ok_or(EncodeError::BufferFull)?
+                        .copy_from_slice(&bytes);
+
+                    Ok (bytes.len())
+                }
+
+                fn decode(buf: &[u8]) -> Result<(Self, usize), EncodeError> {
+                    const SIZE: usize = core::mem::size_of::<$ty>();
+
+                    let mut bytes = [0; SIZE];
+                    bytes.copy_from_slice(buf.get(..SIZE).ok_or(EncodeError::Truncated)?);
+
+                    Ok ((<$ty>::from_le_bytes(bytes), SIZE))
+                }
+            }
+        )*
+    };
+}
+
+encode_le!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+impl Encode for bool {
+    fn encode(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        (*self as u8).encode(buf)
+    }
+
+    fn decode(buf: &[u8]) -> Result<(Self, usize), EncodeError> {
+        match u8::decode(buf)? {
+            (0, len) => Ok ((false, len)),
+            (1, len) => Ok ((true, len)),
+            _ => Err(EncodeError::Invalid)
+        }
+    }
+}
+
+impl Encode for char {
+    fn encode(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        (*self as u32).encode(buf)
+    }
+
+    fn decode(buf: &[u8]) -> Result<(Self, usize), EncodeError> {
+        let (value, len) = u32::decode(buf)?;
+
+        char::from_u32(value)
+            .map(|value| (value, len))
+            .ok_or(EncodeError::Invalid)
+    }
+}
+
+impl Encode for () {
+    fn encode(&self, _buf: &mut [u8]) -> Result<usize, EncodeError> {
+        Ok (0)
+    }
+
+    fn decode(_buf: &[u8]) -> Result<(Self, usize), EncodeError> {
+        Ok (((), 0))
+    }
+}
+
+impl<T: Encode, const N: usize> Encode for [T; N] {
+    fn encode(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        let mut len = 0;
+        for value in self {
+            len += value.encode(&mut buf[len..])?;
+        }
+
+        Ok (len)
+    }
+
+    fn decode(buf: &[u8]) -> Result<(Self, usize), EncodeError> {
+        let mut len = 0;
+        let mut error = None;
+
+        let values = core::array::from_fn(|_| {
+            if error.is_some() {
+                return None;
+            }
+
+            match T::decode(&buf[len..]) {
+                Ok ((value, read)) => {
+                    len += read;
+                    Some (value)
+                },
+                Err (err) => {
+                    error = Some (err);
+                    None
+                }
+            }
+        });
+
+        match error {
+            Some (err) => Err(err),
+            None => Ok ((values.map(|value: Option<T>| value.unwrap()), len))
+        }
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        match self {
+            Some (value) => {
+                let len = 1u8.encode(buf)?;
+                Ok (len + value.encode(&mut buf[len..])?)
+            },
+            None => 0u8.encode(buf)
+        }
+    }
+
+    fn decode(buf: &[u8]) -> Result<(Self, usize), EncodeError> {
+        match u8::decode(buf)? {
+            (0, len) => Ok ((None, len)),
+            (1, len) => {
+                let (value, read) = T::decode(&buf[len..])?;
+                Ok ((Some (value), len + read))
+            },
+            _ => Err(EncodeError::Invalid)
+        }
+    }
+}
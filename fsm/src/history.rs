@@ -0,0 +1,128 @@
+// A bounded record of the latest transitions of a machine
+//
+// `History` is an observer keeping the last N transitions in a ring buffer,
+// each with the tick of its clock at which it happened. It does not allocate
+// and prints as one transition per line, oldest first:
+//
+//     100: Closed --Open--> Opened
+//     250: Opened --Close--> Closed
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::{
+    observer::{Named, Observer},
+    queue::NoClock,
+    Clock,
+    Dispatch
+};
+
+// A transition recorded by `History`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Record<I, E> {
+    pub from: Named<I>,
+    pub event: Named<E>,
+    pub to: Named<I>,
+    // The tick of the clock of the history at which the transition happened
+    pub at: u64
+}
+
+impl<I, E> fmt::Display for Record<I, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} --{}--> {}", self.at, self.from.name, self.event.name, self.to.name)
+    }
+}
+
+// The last N transitions of a machine
+pub struct History<M: Dispatch, const N: usize, C: Clock = NoClock> {
+    records: [Option<Record<M::Id, M::Event>>; N],
+    next: usize,
+    len: usize,
+    clock: C,
+    _m: PhantomData<M>
+}
+
+impl<M: Dispatch, const N: usize> History<M, N> {
+    // An empty history without timestamps
+    pub const fn new() -> Self {
+        Self::with_clock(NoClock)
+    }
+}
+
+impl<M: Dispatch, const N: usize> Default for History<M, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M: Dispatch, const N: usize, C: Clock> History<M, N, C> {
+    // An empty history timestamping the transitions with `clock`
+    pub const fn with_clock(clock: C) -> Self {
+        Self {
+            records: [const { None }; N],
+            next: 0,
+            len: 0,
+            clock,
+            _m: PhantomData
+        }
+    }
+
+    // The number of recorded transitions
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // The recorded transitions, oldest first
+    pub fn iter(&self) -> impl Iterator<Item = &Record<M::Id, M::Event>> + '_ {
+        let start = (self.next + N - self.len) % N.max(1);
+
+        (0..self.len).filter_map(move |i| self.records[(start + i) % N].as_ref())
+    }
+
+    // The latest recorded transition
+    pub fn last(&self) -> Option<&Record<M::Id, M::Event>> {
+        if self.is_empty() {
+            return None;
+        }
+
+        self.records[(self.next + N - 1) % N].as_ref()
+    }
+
+    // Forget every recorded transition
+    pub fn clear(&mut self) {
+        self.records.iter_mut().for_each(|record| *record = None);
+        self.next = 0;
+        self.len = 0;
+    }
+
+    fn push(&mut self, record: Record<M::Id, M::Event>) {
+        if N == 0 {
+            return;
+        }
+
+        self.records[self.next] = Some (record);
+        self.next = (self.next + 1) % N;
+        self.len = (self.len + 1).min(N);
+    }
+}
+
+impl<M: Dispatch, const N: usize, C: Clock> Observer<M> for History<M, N, C> {
+    fn on_transition(&mut self, from: Named<M::Id>, event: Named<M::Event>, to: Named<M::Id>) {
+        let at = self.clock.now();
+        self.push(Record { from, event, to, at });
+    }
+}
+
+impl<M: Dispatch, const N: usize, C: Clock> fmt::Display for History<M, N, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for record in self.iter() {
+            writeln!(f, "{}", record)?;
+        }
+
+        Ok (())
+    }
+}
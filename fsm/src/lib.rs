@@ -1,5 +1,76 @@
 #![no_std]
-pub use fsm_macro::fsm;
+
+#[cfg(any(feature = "proptest", feature = "quickcheck", feature = "check", feature = "coverage"))]
+extern crate alloc;
+
+pub use fsm_macro::{fsm, fsm_scxml};
+
+mod encode;
+mod change;
+mod queue;
+mod observer;
+mod history;
+mod replay;
+mod assert;
+
+#[doc(hidden)]
+#[path = "private.rs"]
+pub mod __private;
+
+pub use encode::{Encode, EncodeError};
+pub use change::Change;
+pub use queue::{Queue, Runner, NoClock, FakeClock};
+pub use observer::{Observer, Named};
+pub use history::{History, Record};
+pub use replay::{replay, ReplayError, Violation};
+
+#[cfg(feature = "log")]
+pub use observer::LogObserver;
+
+#[cfg(feature = "tracing")]
+pub use observer::TracingObserver;
+
+#[cfg(feature = "async")]
+mod stream;
+
+#[cfg(feature = "async")]
+pub use stream::Driver;
+
+#[cfg(any(feature = "proptest", feature = "quickcheck", feature = "check"))]
+mod walk;
+
+#[cfg(any(feature = "proptest", feature = "quickcheck", feature = "check"))]
+pub use walk::Walk;
+
+#[cfg(feature = "check")]
+mod check;
+
+#[cfg(feature = "check")]
+pub use check::Model;
+
+#[cfg(feature = "coverage")]
+mod coverage;
+
+#[cfg(feature = "coverage")]
+pub use coverage::{Coverage, CoverageJson};
+
+#[cfg(feature = "proptest")]
+pub use walk::{walk, walks};
+
+// Used by the code generated for the `serde` feature
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub use serde;
+
+// Used by the code generated for the `proptest` feature
+#[cfg(feature = "proptest")]
+#[doc(hidden)]
+pub use proptest;
+
+// Used by the code generated for the `quickcheck` feature
+#[cfg(feature = "quickcheck")]
+#[doc(hidden)]
+pub use quickcheck;
 
 // A marker trait for an event
 pub trait Event { }
@@ -37,6 +108,79 @@ pub trait ToMemEnum {
 // A marker trait for an entry point state
 pub trait EntryPoint { }
 
+// A marker trait for a final state
+pub trait ExitPoint { }
+
+// A trait for the runtime representation of a machine, implemented by the
+// generated `Variants`
+pub trait Dispatch: Sized {
+    // The ID of a state, the generated `StateId`
+    type Id: Copy + Eq + 'static;
+    // An event of the machine, the generated `Events`
+    type Event: Copy + Eq + 'static;
+
+    // The name of the machine
+    const NAME: &'static str;
+
+    // The ID of the current state
+    fn id(&self) -> Self::Id;
+
+    // Apply `event`, or give the machine back unchanged if the current state
+    // has no transition for it, exactly when `next` has none
+    fn dispatch(self, event: Self::Event) -> Result<Self, Self>;
+
+    // The state `event` leads to from the state `id` at runtime, if any
+    fn next(id: Self::Id, event: Self::Event) -> Option<Self::Id>;
+
+    // The timed events leaving the state `id` and their delays in clock ticks
+    fn timers(id: Self::Id) -> &'static [(Self::Event, u64)];
+
+    // The name of the state `id`
+    fn state_name(id: Self::Id) -> &'static str;
+
+    // The name of `event`
+    fn event_name(event: Self::Event) -> &'static str;
+}
+
+// A trait for the transition table of a machine, implemented by the
+// generated `StateId`
+pub trait Table: Copy + Eq + core::fmt::Debug + 'static {
+    // An event of the machine, the generated `Events`
+    type Event: Copy + Eq + core::fmt::Debug + 'static;
+
+    // Every state, in the order of their IDs
+    const STATES: &'static [Self];
+    // Every event, in declaration order
+    const EVENTS: &'static [Self::Event];
+    // The states the machine can be entered in, none if it has no `Init`
+    // block and is only ever restored from storage
+    const INITS: &'static [Self];
+
+    // The state `event` leads to from this state at runtime, if any
+    //
+    // A transition into a state whose memory differs and has no `Default`
+    // is only available through the typestate API and left out here, like
+    // `Dispatch::dispatch` rejects it.
+    fn next(self, event: Self::Event) -> Option<Self>;
+
+    // The name of the state
+    fn name(self) -> &'static str;
+
+    // The name of `event`
+    fn event_name(event: Self::Event) -> &'static str;
+}
+
+// A monotonic source of ticks, e.g. milliseconds since boot
+pub trait Clock {
+    fn now(&self) -> u64;
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now(&self) -> u64 {
+        (**self).now()
+    }
+}
+
 // A trait defining an entrypoint event for state S
 pub trait Init<S: State + EntryPoint> {
     type SM: SM<State = S>;
@@ -0,0 +1,113 @@
+// Hooks called by the runtime drivers on every dispatched event
+//
+// `Runner` and `Driver` call an observer after each event they dispatch,
+// with the IDs and names of the states and the event. Observers combine as
+// tuples, and `()` observes nothing.
+
+use crate::{
+    Change,
+    Dispatch
+};
+
+// An ID of a state or event together with its name
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Named<T> {
+    pub id: T,
+    pub name: &'static str
+}
+
+// A trait for the hooks called on the events dispatched on a machine
+pub trait Observer<M: Dispatch> {
+    // The event moved the machine from `from` to `to`
+    fn on_transition(&mut self, _from: Named<M::Id>, _event: Named<M::Event>, _to: Named<M::Id>) { }
+
+    // The state has no transition for the event, the machine is unchanged
+    fn on_rejected(&mut self, _state: Named<M::Id>, _event: Named<M::Event>) { }
+}
+
+impl<M: Dispatch> Observer<M> for () { }
+
+impl<M: Dispatch, O: Observer<M> + ?Sized> Observer<M> for &mut O {
+    fn on_transition(&mut self, from: Named<M::Id>, event: Named<M::Event>, to: Named<M::Id>) {
+        (**self).on_transition(from, event, to);
+    }
+
+    fn on_rejected(&mut self, state: Named<M::Id>, event: Named<M::Event>) {
+        (**self).on_rejected(state, event);
+    }
+}
+
+impl<M: Dispatch, A: Observer<M>, B: Observer<M>> Observer<M> for (A, B) {
+    fn on_transition(&mut self, from: Named<M::Id>, event: Named<M::Event>, to: Named<M::Id>) {
+        self.0.on_transition(from, event, to);
+        self.1.on_transition(from, event, to);
+    }
+
+    fn on_rejected(&mut self, state: Named<M::Id>, event: Named<M::Event>) {
+        self.0.on_rejected(state, event);
+        self.1.on_rejected(state, event);
+    }
+}
+
+// Call the hook of `observer` matching `change`
+pub(crate) fn notify<M: Dispatch, O: Observer<M>>(observer: &mut O, change: Change<M::Id, M::Event>) {
+    let state = |id| Named { id, name: M::state_name(id) };
+    let event = |id| Named { id, name: M::event_name(id) };
+
+    match change {
+        Change::Moved { from, event: id, to } => observer.on_transition(state(from), event(id), state(to)),
+        Change::Rejected { state: id, event: e } => observer.on_rejected(state(id), event(e))
+    }
+}
+
+// Logs every transition at debug level and every rejected event at warn level
+#[cfg(feature = "log")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LogObserver;
+
+#[cfg(feature = "log")]
+impl<M: Dispatch> Observer<M> for LogObserver {
+    fn on_transition(&mut self, from: Named<M::Id>, event: Named<M::Event>, to: Named<M::Id>) {
+        log::debug!("{}: {} --{}--> {}", M::NAME, from.name, event.name, to.name);
+    }
+
+    fn on_rejected(&mut self, state: Named<M::Id>, event: Named<M::Event>) {
+        log::warn!("{}: {} has no transition for {}", M::NAME, state.name, event.name);
+    }
+}
+
+// Opens a `state` span for every state the machine enters and records the
+// transitions and rejected events inside the span of the state they leave
+#[cfg(feature = "tracing")]
+#[derive(Debug, Default)]
+pub struct TracingObserver {
+    span: Option<tracing::Span>
+}
+
+#[cfg(feature = "tracing")]
+impl TracingObserver {
+    pub const fn new() -> Self {
+        Self {
+            span: None
+        }
+    }
+
+    fn span<M: Dispatch>(&mut self, state: Named<M::Id>) -> &tracing::Span {
+        self.span.get_or_insert_with(|| tracing::info_span!("state", machine = M::NAME, state = state.name))
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl<M: Dispatch> Observer<M> for TracingObserver {
+    fn on_transition(&mut self, from: Named<M::Id>, event: Named<M::Event>, to: Named<M::Id>) {
+        let span = self.span::<M>(from);
+        tracing::info!(parent: span, machine = M::NAME, from = from.name, event = event.name, to = to.name, "transition");
+
+        self.span = Some (tracing::info_span!("state", machine = M::NAME, state = to.name));
+    }
+
+    fn on_rejected(&mut self, state: Named<M::Id>, event: Named<M::Event>) {
+        let span = self.span::<M>(state);
+        tracing::warn!(parent: span, machine = M::NAME, state = state.name, event = event.name, "rejected");
+    }
+}
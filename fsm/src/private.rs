@@ -0,0 +1,133 @@
+// Support for the generated code, not part of the public API
+//
+// The code generated by `fsm!` is the same whatever the features of
+// `static-fsm`. It wraps the optional implementations in these macros, which
+// keep or drop them according to the features of this crate: the proc macro
+// crate cannot decide, since Cargo may build it with other features.
+
+use core::marker::PhantomData;
+
+pub use crate::{
+    __static_fsm_serde as serde,
+    __static_fsm_serde_derive as serde_derive,
+    __static_fsm_proptest as proptest,
+    __static_fsm_quickcheck as quickcheck
+};
+
+// The items, if the `serde` feature is enabled
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __static_fsm_serde {
+    ($($item:item)*) => { $($item)* };
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __static_fsm_serde {
+    ($($item:item)*) => { };
+}
+
+// The item, deriving `Serialize` and `Deserialize` if the `serde` feature is
+// enabled
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __static_fsm_serde_derive {
+    ($item:item) => {
+        #[derive($crate::serde::Serialize, $crate::serde::Deserialize)]
+        #[serde(crate = "static_fsm::serde")]
+        $item
+    };
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __static_fsm_serde_derive {
+    ($item:item) => { $item };
+}
+
+// The items, if the `proptest` feature is enabled
+#[cfg(feature = "proptest")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __static_fsm_proptest {
+    ($($item:item)*) => { $($item)* };
+}
+
+#[cfg(not(feature = "proptest"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __static_fsm_proptest {
+    ($($item:item)*) => { };
+}
+
+// The items, if the `quickcheck` feature is enabled
+#[cfg(feature = "quickcheck")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __static_fsm_quickcheck {
+    ($($item:item)*) => { $($item)* };
+}
+
+#[cfg(not(feature = "quickcheck"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __static_fsm_quickcheck {
+    ($($item:item)*) => { };
+}
+
+// The memory a state starts with when entered from a state with other memory
+//
+// `(&Memory::<T>::new()).memory()` resolves to `DefaultMemory` if `T`
+// implements `Default` and falls back to `NoDefaultMemory` otherwise, which
+// lets `dispatch` reject the transition instead of requiring `Default` of
+// every memory type. `has_default` resolves the same way, so that `next`
+// leaves out the transitions `dispatch` rejects.
+pub struct Memory<T>(PhantomData<T>);
+
+impl<T> Memory<T> {
+    pub const fn new() -> Self {
+        Memory(PhantomData)
+    }
+}
+
+impl<T> Default for Memory<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub trait DefaultMemory<T> {
+    fn memory(&self) -> Option<T>;
+
+    fn has_default(&self) -> bool;
+}
+
+impl<T: Default> DefaultMemory<T> for Memory<T> {
+    fn memory(&self) -> Option<T> {
+        Some (T::default())
+    }
+
+    fn has_default(&self) -> bool {
+        true
+    }
+}
+
+pub trait NoDefaultMemory<T> {
+    fn memory(&self) -> Option<T>;
+
+    fn has_default(&self) -> bool;
+}
+
+impl<T> NoDefaultMemory<T> for &Memory<T> {
+    fn memory(&self) -> Option<T> {
+        None
+    }
+
+    fn has_default(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,305 @@
+// Run-to-completion processing of events
+//
+// Events posted while an event is processed, e.g. from the handler of a
+// transition, wait in a fixed-capacity queue and are only dispatched once
+// the current transition and its handler have finished. Neither the queue
+// nor the runner allocate.
+//
+// A runner with a clock also fires the timed events of the machine: the
+// timers of a state start when the state is entered, and `poll` dispatches
+// the first timed event whose delay has elapsed since. A state entered by a
+// timer counts as entered at the deadline of that timer, not when `poll`
+// happened to notice it, so that chained timers keep their schedule.
+//
+// A runner with an observer calls it after every event, before the handler.
+
+use core::cell::Cell;
+
+use crate::{
+    change::{self, Change},
+    observer::{self, Observer},
+    Clock,
+    Dispatch
+};
+
+// A fixed-capacity FIFO queue of events
+pub struct Queue<E, const N: usize> {
+    events: [Option<E>; N],
+    head: usize,
+    len: usize
+}
+
+impl<E, const N: usize> Queue<E, N> {
+    // An empty queue
+    pub const fn new() -> Self {
+        Self {
+            events: [const { None }; N],
+            head: 0,
+            len: 0
+        }
+    }
+
+    // The number of events the queue can hold
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    // The number of queued events
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub const fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    // Queue `event` behind the queued events, or give it back if the queue is full
+    pub fn post(&mut self, event: E) -> Result<(), E> {
+        if self.is_full() {
+            return Err(event);
+        }
+
+        self.events[(self.head + self.len) % N] = Some (event);
+        self.len += 1;
+
+        Ok (())
+    }
+
+    // Remove the oldest queued event
+    pub fn pop(&mut self) -> Option<E> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let event = self.events[self.head].take();
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+
+        event
+    }
+
+    // Drop every queued event
+    pub fn clear(&mut self) {
+        while self.pop().is_some() { }
+    }
+}
+
+impl<E, const N: usize> Default for Queue<E, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// The clock of a runner without timers, which never advances
+pub struct NoClock;
+
+impl Clock for NoClock {
+    fn now(&self) -> u64 {
+        0
+    }
+}
+
+// A clock advanced by hand, for deterministic tests of timed events
+#[derive(Default)]
+pub struct FakeClock {
+    now: Cell<u64>
+}
+
+impl FakeClock {
+    // A clock at tick 0
+    pub const fn new() -> Self {
+        Self {
+            now: Cell::new(0)
+        }
+    }
+
+    // Move the clock `ticks` ticks forward
+    pub fn advance(&self, ticks: u64) {
+        self.now.set(self.now.get() + ticks);
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> u64 {
+        self.now.get()
+    }
+}
+
+// A machine with a run-to-completion queue of up to N events
+pub struct Runner<M: Dispatch, const N: usize, C: Clock = NoClock, O: Observer<M> = ()> {
+    machine: Option<M>,
+    queue: Queue<M::Event, N>,
+    clock: C,
+    entered: u64,
+    observer: O
+}
+
+impl<M: Dispatch, const N: usize> Runner<M, N> {
+    // Run `machine` with an empty queue and without timers
+    pub const fn new(machine: M) -> Self {
+        Self {
+            machine: Some (machine),
+            queue: Queue::new(),
+            clock: NoClock,
+            entered: 0,
+            observer: ()
+        }
+    }
+}
+
+impl<M: Dispatch, const N: usize, C: Clock> Runner<M, N, C> {
+    // Run `machine` with an empty queue, firing its timed events by `clock`
+    pub fn with_clock(machine: M, clock: C) -> Self {
+        let entered = clock.now();
+
+        Self {
+            machine: Some (machine),
+            queue: Queue::new(),
+            clock,
+            entered,
+            observer: ()
+        }
+    }
+}
+
+impl<M: Dispatch, const N: usize, C: Clock, O: Observer<M>> Runner<M, N, C, O> {
+    // Call `observer` after every event
+    pub fn with_observer<P: Observer<M>>(self, observer: P) -> Runner<M, N, C, P> {
+        Runner {
+            machine: self.machine,
+            queue: self.queue,
+            clock: self.clock,
+            entered: self.entered,
+            observer
+        }
+    }
+
+    // The observer of the runner
+    pub fn observer(&self) -> &O {
+        &self.observer
+    }
+
+    pub fn observer_mut(&mut self) -> &mut O {
+        &mut self.observer
+    }
+
+    // The clock of the runner
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    // The tick at which the next timer of the current state expires, if any
+    pub fn deadline(&self) -> Option<u64> {
+        self.timers()
+            .map(|(_, delay)| self.entered.saturating_add(delay))
+            .min()
+    }
+
+    // The timed events of the current state that have a transition at
+    // runtime, see `Dispatch::next`
+    fn timers(&self) -> impl Iterator<Item = (M::Event, u64)> + '_ {
+        let id = self.machine().id();
+
+        M::timers(id).iter()
+            .copied()
+            .filter(move |(event, _)| M::next(id, *event).is_some())
+    }
+
+    // Dispatch the timed event of the current state that expired first, if
+    // any, then process the queue, see `run`
+    //
+    // The timer is dispatched ahead of the queued events, while the state
+    // that armed it is still current.
+    pub fn poll<F>(&mut self, mut handler: F) -> usize
+    where
+        F: FnMut(&M, Change<M::Id, M::Event>, &mut Queue<M::Event, N>)
+    {
+        let now = self.clock.now();
+        let expired = self.timers()
+            .filter(|(_, delay)| self.entered.saturating_add(*delay) <= now)
+            .min_by_key(|(_, delay)| *delay);
+
+        let fired = match expired {
+            Some ((event, delay)) => {
+                self.step(event, self.entered.saturating_add(delay), &mut handler);
+                1
+            },
+            None => 0
+        };
+
+        fired + self.run(handler)
+    }
+
+    // The current machine
+    pub fn machine(&self) -> &M {
+        self.machine.as_ref().expect("the machine is only taken while dispatching")
+    }
+
+    // The events waiting to be processed
+    pub fn queue(&self) -> &Queue<M::Event, N> {
+        &self.queue
+    }
+
+    // Stop running and return the machine, dropping the queued events
+    pub fn into_inner(self) -> M {
+        self.machine.expect("the machine is only taken while dispatching")
+    }
+
+    // Queue `event`, or give it back if the queue is full
+    pub fn post(&mut self, event: M::Event) -> Result<(), M::Event> {
+        self.queue.post(event)
+    }
+
+    // Process the queued events in order until the queue is empty
+    //
+    // `handler` is called after each event with the machine, the change the
+    // event made and the queue, to which it can post further events. Returns
+    // the number of processed events.
+    pub fn run<F>(&mut self, mut handler: F) -> usize
+    where
+        F: FnMut(&M, Change<M::Id, M::Event>, &mut Queue<M::Event, N>)
+    {
+        let mut processed = 0;
+
+        while let Some(event) = self.queue.pop() {
+            let now = self.clock.now();
+            self.step(event, now, &mut handler);
+            processed += 1;
+        }
+
+        processed
+    }
+
+    // Dispatch `event`, which happened at tick `at`, then notify the observer
+    // and call `handler`
+    fn step<F>(&mut self, event: M::Event, at: u64, handler: &mut F)
+    where
+        F: FnMut(&M, Change<M::Id, M::Event>, &mut Queue<M::Event, N>)
+    {
+        let machine = self.machine.take().expect("the machine is only taken while dispatching");
+        let (machine, change) = change::apply(machine, event);
+        let machine = self.machine.insert(machine);
+
+        if let Change::Moved { .. } = change {
+            self.entered = at;
+        }
+
+        observer::notify(&mut self.observer, change);
+
+        handler(machine, change, &mut self.queue);
+    }
+
+    // Queue `event` and process the queue, see `run`
+    pub fn handle<F>(&mut self, event: M::Event, handler: F) -> Result<usize, M::Event>
+    where
+        F: FnMut(&M, Change<M::Id, M::Event>, &mut Queue<M::Event, N>)
+    {
+        self.post(event)?;
+
+        Ok (self.run(handler))
+    }
+}
@@ -0,0 +1,116 @@
+// Replaying recorded event logs against the transition table of a machine
+//
+// A log has one event name per line. `Event => State` also asserts the state
+// the event leads to, and `= State` asserts the current state. Blank lines
+// and lines starting with `#` are ignored:
+//
+//     = Closed
+//     Open => Opened
+//     # closed by hand
+//     Close
+//
+// Replaying stops at the first line that is not a legal step of the machine.
+
+use core::fmt;
+
+use crate::Table;
+
+// Why a line of a log is not a legal step of the machine
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Violation<'a, T: Table> {
+    // The line names no event of the machine
+    UnknownEvent(&'a str),
+    // The line asserts the state after an event, but names no event
+    MissingEvent,
+    // The line asserts a state the machine does not have
+    UnknownState(&'a str),
+    // The current state has no transition for the event
+    Rejected {
+        state: T,
+        event: T::Event
+    },
+    // The machine is not in the asserted state
+    Mismatch {
+        expected: T,
+        actual: T
+    }
+}
+
+// The first line of a log that is not a legal step of the machine
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReplayError<'a, T: Table> {
+    // The number of the line, starting at 1
+    pub line: usize,
+    // The text of the line, without surrounding whitespace
+    pub text: &'a str,
+    pub violation: Violation<'a, T>
+}
+
+impl<T: Table> fmt::Display for ReplayError<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: `{}`: ", self.line, self.text)?;
+
+        match self.violation {
+            Violation::UnknownEvent(name) => write!(f, "unknown event {}", name),
+            Violation::MissingEvent => write!(f, "missing event before `=>`"),
+            Violation::UnknownState(name) => write!(f, "unknown state {}", name),
+            Violation::Rejected { state, event } => write!(f, "{} has no transition for {}", state.name(), T::event_name(event)),
+            Violation::Mismatch { expected, actual } => write!(f, "expected state {}, but the machine is in {}", expected.name(), actual.name())
+        }
+    }
+}
+
+// Replay `log` from the state `start` and return the state it ends in
+pub fn replay<T: Table>(start: T, log: &str) -> Result<T, ReplayError<'_, T>> {
+    let mut state = start;
+
+    for (i, text) in log.lines().enumerate() {
+        let text = text.trim();
+        let error = |violation| ReplayError { line: i + 1, text, violation };
+
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+
+        // `=> State` is an event line missing its event, not `= > State`
+        if let Some(name) = text.strip_prefix('=').filter(|_| !text.starts_with("=>")) {
+            let expected = parse_state(name.trim()).map_err(error)?;
+            if expected != state {
+                return Err(error(Violation::Mismatch { expected, actual: state }));
+            }
+
+            continue;
+        }
+
+        let (event, expected) = match text.split_once("=>") {
+            Some ((event, expected)) => (event.trim(), Some (parse_state(expected.trim()).map_err(error)?)),
+            None => (text, None)
+        };
+
+        if event.is_empty() {
+            return Err(error(Violation::MissingEvent));
+        }
+
+        let event = T::EVENTS.iter()
+            .copied()
+            .find(|candidate| T::event_name(*candidate) == event)
+            .ok_or(error(Violation::UnknownEvent(event)))?;
+
+        state = state.next(event).ok_or(error(Violation::Rejected { state, event }))?;
+
+        if let Some(expected) = expected {
+            if expected != state {
+                return Err(error(Violation::Mismatch { expected, actual: state }));
+            }
+        }
+    }
+
+    Ok (state)
+}
+
+fn parse_state<T: Table>(name: &str) -> Result<T, Violation<'_, T>> {
+    T::STATES.iter()
+        .copied()
+        .find(|state| state.name() == name)
+        .ok_or(Violation::UnknownState(name))
+}
@@ -0,0 +1,104 @@
+// A runtime-agnostic driver applying a stream of events to a machine
+//
+// The driver only relies on `futures_core::Stream`, so it runs on any
+// executor, including a simple local one in tests:
+//
+//     let mut driver = Driver::new(door::FSM::<door::Closed>::init().to_enum(), events);
+//     while let Some(change) = driver.next().await {
+//         ...
+//     }
+
+use core::pin::Pin;
+use core::task::{
+    Context,
+    Poll
+};
+
+use futures_core::Stream;
+
+use crate::{
+    change::{self, Change},
+    observer::{self, Observer},
+    Dispatch
+};
+
+// Applies every event of a stream to a machine and yields the changes
+pub struct Driver<M, S, O = ()> {
+    machine: Option<M>,
+    events: S,
+    observer: O
+}
+
+impl<M, S> Driver<M, S>
+where
+    M: Dispatch,
+    S: Stream<Item = M::Event>
+{
+    // Drive `machine` with `events`
+    pub fn new(machine: M, events: S) -> Self {
+        Self {
+            machine: Some (machine),
+            events,
+            observer: ()
+        }
+    }
+}
+
+impl<M, S, O> Driver<M, S, O>
+where
+    M: Dispatch,
+    S: Stream<Item = M::Event>,
+    O: Observer<M>
+{
+    // Call `observer` after every event
+    pub fn with_observer<P: Observer<M>>(self, observer: P) -> Driver<M, S, P> {
+        Driver {
+            machine: self.machine,
+            events: self.events,
+            observer
+        }
+    }
+
+    // The observer of the driver
+    pub fn observer(&self) -> &O {
+        &self.observer
+    }
+
+    // The current machine
+    pub fn machine(&self) -> &M {
+        self.machine.as_ref().expect("the machine is only taken while dispatching")
+    }
+
+    // Stop driving and return the machine and the remaining events
+    pub fn into_inner(self) -> (M, S) {
+        (self.machine.expect("the machine is only taken while dispatching"), self.events)
+    }
+}
+
+impl<M, S, O> Stream for Driver<M, S, O>
+where
+    M: Dispatch + Unpin,
+    S: Stream<Item = M::Event> + Unpin,
+    O: Observer<M> + Unpin
+{
+    type Item = Change<M::Id, M::Event>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let event = match Pin::new(&mut self.events).poll_next(cx) {
+            Poll::Ready(Some (event)) => event,
+            Poll::Ready(None) => return Poll::Ready(None),
+            Poll::Pending => return Poll::Pending
+        };
+
+        let machine = self.machine.take().expect("the machine is only taken while dispatching");
+        let (machine, change) = change::apply(machine, event);
+
+        self.machine = Some (machine);
+        observer::notify(&mut self.observer, change);
+        Poll::Ready(Some (change))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.events.size_hint()
+    }
+}
@@ -0,0 +1,164 @@
+// Random walks over the transition table of a machine, for property-based
+// tests
+//
+// A `Walk` is a sequence of events that is legal from its start state: every
+// event has a transition from the state the previous events lead to. Walks
+// are built from a list of choices, each picking one of the events legal in
+// the current state, and end early in a state without transitions. Shrinking
+// the choices therefore always gives a legal walk again.
+//
+// With the `proptest` feature, `walk(start, len)` and `walks(len)` are
+// strategies of walks. With the `quickcheck` feature, `Walk` implements
+// `quickcheck::Arbitrary` and starts in a random `Init` state. The model
+// checker of the `check` feature returns walks as counterexamples. A machine
+// without `Init` block is only ever restored, in any of its states, so its
+// walks start in any state.
+//
+// A walk prints as its states and events:
+//
+//     Closed --Open--> Opened --Close--> Closed
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::Table;
+
+// A sequence of events legal from `start`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Walk<T: Table> {
+    pub start: T,
+    pub events: Vec<T::Event>
+}
+
+impl<T: Table> Walk<T> {
+    // The walk from `start` taking the `choice % n`th of the n events legal
+    // in the current state, for every choice until a state has none
+    pub fn new<I: IntoIterator<Item = usize>>(start: T, choices: I) -> Self {
+        let mut state = start;
+        let mut events = Vec::new();
+
+        for choice in choices {
+            let legal = legal(state).count();
+            if legal == 0 {
+                break;
+            }
+
+            let event = legal_nth(state, choice % legal);
+            state = state.next(event).expect("the event is legal in the state");
+            events.push(event);
+        }
+
+        Self { start, events }
+    }
+
+    // Whether every event has a transition from the state the previous
+    // events lead to, as for every walk built by `new`
+    pub fn is_legal(&self) -> bool {
+        self.states().count() == self.events.len() + 1
+    }
+
+    // The states the walk passes through, starting with `start`
+    pub fn states(&self) -> impl Iterator<Item = T> + '_ {
+        let mut state = Some (self.start);
+
+        core::iter::once(self.start).chain(self.events.iter().filter_map(move |event| {
+            state = state.and_then(|current| current.next(*event));
+            state
+        }))
+    }
+
+    // The state the walk ends in
+    pub fn end(&self) -> T {
+        self.states().last().unwrap_or(self.start)
+    }
+
+    // The transitions of the walk as `(from, event, to)`, in order
+    pub fn steps(&self) -> impl Iterator<Item = (T, T::Event, T)> + '_ {
+        self.states().zip(self.events.iter().copied()).zip(self.states().skip(1))
+            .map(|((from, event), to)| (from, event, to))
+    }
+
+    // The walk extended by `event`, if it is legal in the state the walk
+    // ends in
+    pub fn then(&self, event: T::Event) -> Option<Self> {
+        self.end().next(event)?;
+
+        let mut events = self.events.clone();
+        events.push(event);
+
+        Some (Self { start: self.start, events })
+    }
+}
+
+impl<T: Table> fmt::Display for Walk<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.start.name())?;
+
+        for (_, event, to) in self.steps() {
+            write!(f, " --{}--> {}", T::event_name(event), to.name())?;
+        }
+
+        Ok (())
+    }
+}
+
+// The events with a transition from `state`
+pub(crate) fn legal<T: Table>(state: T) -> impl Iterator<Item = T::Event> {
+    T::EVENTS.iter().copied().filter(move |event| state.next(*event).is_some())
+}
+
+// The states a walk can start in: the `Init` states, or every state of a
+// machine without `Init` block
+pub(crate) fn starts<T: Table>() -> &'static [T] {
+    if T::INITS.is_empty() {
+        T::STATES
+    } else {
+        T::INITS
+    }
+}
+
+fn legal_nth<T: Table>(state: T, n: usize) -> T::Event {
+    legal(state).nth(n).expect("fewer legal events than chosen")
+}
+
+// Walks from `start` with a number of choices in `len`
+#[cfg(feature = "proptest")]
+pub fn walk<T: Table>(start: T, len: impl Into<proptest::collection::SizeRange>) -> impl proptest::strategy::Strategy<Value = Walk<T>> {
+    use proptest::strategy::Strategy;
+
+    proptest::collection::vec(proptest::arbitrary::any::<usize>(), len)
+        .prop_map(move |choices| Walk::new(start, choices))
+}
+
+// Walks from any `Init` state, or any state of a machine without `Init`
+// block, with a number of choices in `len`
+#[cfg(feature = "proptest")]
+pub fn walks<T: Table>(len: impl Into<proptest::collection::SizeRange>) -> impl proptest::strategy::Strategy<Value = Walk<T>> {
+    use proptest::strategy::Strategy;
+
+    let choices = proptest::collection::vec(proptest::arbitrary::any::<usize>(), len);
+
+    (proptest::sample::select(starts::<T>()), choices)
+        .prop_map(|(start, choices)| Walk::new(start, choices))
+}
+
+#[cfg(feature = "quickcheck")]
+impl<T: Table> quickcheck::Arbitrary for Walk<T> {
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        let start = *g.choose(starts::<T>()).expect("a machine has at least one state");
+        let len = usize::arbitrary(g) % (g.size() + 1);
+        let choices: Vec<usize> = (0..len).map(|_| usize::arbitrary(g)).collect();
+
+        Walk::new(start, choices)
+    }
+
+    // The shorter prefixes of the walk, which are legal as well
+    fn shrink(&self) -> alloc::boxed::Box<dyn Iterator<Item = Self>> {
+        let walk = self.clone();
+
+        alloc::boxed::Box::new((0..walk.events.len()).rev().map(move |len| Walk {
+            start: walk.start,
+            events: walk.events[..len].to_vec()
+        }))
+    }
+}
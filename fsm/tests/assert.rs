@@ -0,0 +1,38 @@
+use static_fsm::{assert_no_transition, assert_transition, fsm};
+
+fsm!(door {
+    Init[Closed]
+    Open: Closed => Opened
+    Close: Opened => Closed
+    Lock: Closed => Locked
+    Unlock: Locked => Closed
+});
+
+mod building {
+    use static_fsm::{assert_no_transition, assert_transition, fsm};
+
+    fsm!(gate {
+        Init[Down]
+        Raise: Down => Up
+        Lower: Up => Down
+    });
+
+    assert_transition!(gate: Down --Raise--> Up);
+    assert_no_transition!(gate: Up --Raise);
+}
+
+assert_transition!(door: Closed --Open--> Opened);
+assert_transition!(door: Opened --Close--> Closed);
+assert_transition!(door: Closed --Lock--> Locked);
+assert_transition!(door: Locked --Unlock--> Closed);
+
+assert_no_transition!(door: Locked --Open);
+assert_no_transition!(door: Opened --Lock);
+assert_no_transition!(door: Closed --Close);
+
+assert_transition!(self::door: Opened --Close--> Closed);
+assert_no_transition!(self::door: Locked --Lock);
+
+// The assertions are checked at compile time, compiling this file is the test
+#[test]
+fn assertions_compile() { }
@@ -0,0 +1,116 @@
+#![cfg(feature = "check")]
+
+use static_fsm::{fsm, Model};
+
+fsm!(door {
+    Init[Closed]
+    Open: Closed => Opened
+    Close: Opened => Closed
+    Lock: Closed => Locked
+    Unlock: Locked => Closed
+    Kick: Opened => Broken
+    Final[Broken]
+});
+
+fsm!(vault {
+    Open: Closed => Opened
+    Close: Opened => Closed
+    Seal: Closed => Sealed
+    Final[Sealed]
+});
+
+fn model(depth: usize) -> Model<door::StateId> {
+    Model::new(depth)
+}
+
+#[test]
+fn walks_are_every_walk_shortest_first() {
+    let walks: Vec<String> = model(2).walks().map(|walk| walk.to_string()).collect();
+
+    assert_eq!(walks, [
+        "Closed",
+        "Closed --Open--> Opened",
+        "Closed --Lock--> Locked",
+        "Closed --Open--> Opened --Close--> Closed",
+        "Closed --Open--> Opened --Kick--> Broken",
+        "Closed --Lock--> Locked --Unlock--> Closed"
+    ]);
+}
+
+#[test]
+fn always_holds() {
+    let never_opened_while_locked = |walk: &static_fsm::Walk<door::StateId>| walk.steps().all(|(from, event, _)| {
+        from != door::StateId::Locked || event != door::Events::Open
+    });
+
+    assert_eq!(model(6).always(never_opened_while_locked), Ok (()));
+}
+
+#[test]
+fn always_returns_the_shortest_counterexample() {
+    let walk = model(6).always(|walk| walk.events.len() < 2 || walk.events[1] != door::Events::Kick).unwrap_err();
+
+    assert_eq!(walk.to_string(), "Closed --Open--> Opened --Kick--> Broken");
+}
+
+#[test]
+fn never_holds() {
+    assert_eq!(model(6).never(|state| state.name().is_empty()), Ok (()));
+    assert_eq!(model(1).never(|state| state == door::StateId::Broken), Ok (()));
+}
+
+#[test]
+fn never_returns_the_shortest_counterexample() {
+    let walk = model(6).never(|state| state == door::StateId::Broken).unwrap_err();
+
+    assert_eq!(walk.to_string(), "Closed --Open--> Opened --Kick--> Broken");
+}
+
+#[test]
+fn never_checks_the_start_states() {
+    let walk = model(6).never(|state| state == door::StateId::Closed).unwrap_err();
+
+    assert_eq!(walk.to_string(), "Closed");
+}
+
+#[test]
+fn reaches_holds() {
+    assert_eq!(model(6).reaches(|state| state == door::StateId::Opened || state == door::StateId::Broken), Ok (()));
+}
+
+#[test]
+fn reaches_returns_the_shortest_counterexample() {
+    let walk = model(6).reaches(|state| state == door::StateId::Closed).unwrap_err();
+
+    assert_eq!(walk.to_string(), "Closed --Open--> Opened --Kick--> Broken");
+}
+
+#[test]
+fn reaches_within_the_depth() {
+    // Locked is two events away from Opened
+    let walk = Model::<door::StateId>::new(1).reaches(|state| state == door::StateId::Locked).unwrap_err();
+    assert_eq!(walk.to_string(), "Closed --Open--> Opened");
+
+    let walk = Model::<door::StateId>::new(2).reaches(|state| state == door::StateId::Locked).unwrap_err();
+    assert_eq!(walk.to_string(), "Closed --Open--> Opened --Kick--> Broken");
+}
+
+#[test]
+fn machines_without_init_start_anywhere() {
+    let model = Model::<vault::StateId>::new(4);
+
+    assert_eq!(model.walks().filter(|walk| walk.events.is_empty()).count(), 3);
+    assert_eq!(model.never(|state| state == vault::StateId::Sealed).unwrap_err().to_string(), "Sealed");
+
+    let walk = model.reaches(|state| state == vault::StateId::Opened).unwrap_err();
+    assert_eq!(walk.to_string(), "Sealed");
+}
+
+#[test]
+fn deep_models_stay_fast() {
+    // Every walk of 64 events would be far too many to enumerate
+    let model = model(64);
+
+    assert!(model.never(|state| state == door::StateId::Broken).is_err());
+    assert!(model.reaches(|state| state == door::StateId::Broken).is_ok());
+}
@@ -0,0 +1,50 @@
+// Machines shared by the integration tests
+//
+// `fsm!` generates a private module, so every machine is declared inside a
+// public module of the same name that re-exports its items.
+#![allow(dead_code, unused_imports)]
+
+// A door that opens and closes
+pub mod door {
+    use static_fsm::{fsm, Init};
+
+    fsm!(door {
+        Init[Closed]
+        Open: Closed => Opened
+        Close: Opened => Closed
+    });
+
+    pub use self::door::*;
+
+    // The door in its initial state
+    pub fn closed() -> Variants {
+        FSM::<Closed>::init().to_enum()
+    }
+}
+
+// A door that breaks for good when kicked open
+pub mod fragile {
+    use static_fsm::fsm;
+
+    fsm!(fragile {
+        Init[Closed]
+        Open: Closed => Opened
+        Close: Opened => Closed
+        Kick: Opened => Broken
+        Final[Broken]
+    });
+
+    pub use self::fragile::*;
+}
+
+// A machine without `Init` block, which is only ever restored
+pub mod vault {
+    use static_fsm::fsm;
+
+    fsm!(vault {
+        Open: Closed => Opened
+        Close: Opened => Closed
+    });
+
+    pub use self::vault::*;
+}
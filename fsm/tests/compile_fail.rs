@@ -0,0 +1,5 @@
+// The spanned errors of invalid machines, see `tests/ui`
+#[test]
+fn compile_fail() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,92 @@
+#![cfg(feature = "coverage")]
+
+use static_fsm::{fsm, Coverage, Init, Runner};
+
+fsm!(door {
+    Init[Closed]
+    Open: Closed => Opened
+    Close: Opened => Closed
+    Kick: Opened => Broken
+    Final[Broken]
+});
+
+use door::{Events, StateId};
+
+fn run(events: &[Events]) -> Coverage<door::Variants> {
+    let closed = door::FSM::<door::Closed>::init().to_enum();
+    let mut runner = Runner::<_, 1>::new(closed).with_observer(Coverage::new());
+
+    for event in events {
+        runner.handle(*event, |_, _, _| {}).unwrap();
+    }
+
+    std::mem::take(runner.observer_mut())
+}
+
+#[test]
+fn counts_taken_transitions() {
+    let coverage = run(&[Events::Open, Events::Close, Events::Open]);
+
+    assert_eq!(coverage.hits(StateId::Closed, Events::Open), 2);
+    assert_eq!(coverage.hits(StateId::Opened, Events::Close), 1);
+    assert_eq!(coverage.hits(StateId::Opened, Events::Kick), 0);
+    assert_eq!(coverage.covered(), 2);
+}
+
+#[test]
+fn ignores_rejected_events() {
+    let coverage = run(&[Events::Close, Events::Kick]);
+
+    assert_eq!(coverage.covered(), 0);
+    assert_eq!(coverage.hits(StateId::Closed, Events::Close), 0);
+}
+
+#[test]
+fn lists_declared_and_missed_transitions() {
+    let coverage = run(&[Events::Open, Events::Close]);
+
+    assert_eq!(coverage.declared().collect::<Vec<_>>(), [
+        (StateId::Closed, Events::Open, StateId::Opened),
+        (StateId::Opened, Events::Close, StateId::Closed),
+        (StateId::Opened, Events::Kick, StateId::Broken)
+    ]);
+    assert_eq!(coverage.missed().collect::<Vec<_>>(), [(StateId::Opened, Events::Kick, StateId::Broken)]);
+}
+
+#[test]
+fn merges_and_clears() {
+    let mut coverage = run(&[Events::Open, Events::Close]);
+    coverage.merge(&run(&[Events::Open, Events::Kick]));
+
+    assert_eq!(coverage.hits(StateId::Closed, Events::Open), 2);
+    assert_eq!(coverage.covered(), 3);
+    assert_eq!(coverage.missed().count(), 0);
+
+    coverage.clear();
+    assert_eq!(coverage.covered(), 0);
+    assert_eq!(coverage.missed().count(), 3);
+}
+
+#[test]
+fn prints_missed_transitions() {
+    let coverage = run(&[Events::Open, Events::Close]);
+
+    assert_eq!(coverage.to_string(), "door: 2 of 3 transitions covered\nmissed: Opened --Kick--> Broken\n");
+    assert_eq!(run(&[Events::Open, Events::Close, Events::Open, Events::Kick]).to_string(), "door: 3 of 3 transitions covered\n");
+}
+
+#[test]
+fn writes_json() {
+    let json = run(&[Events::Open, Events::Close, Events::Open]).json().to_string();
+
+    assert!(!json.contains('\n'));
+    assert_eq!(json, concat!(
+        r#"{"machine":"door","covered":2,"declared":3,"transitions":["#,
+        r#"{"from":"Closed","event":"Open","to":"Opened","hits":2},"#,
+        r#"{"from":"Opened","event":"Close","to":"Closed","hits":1},"#,
+        r#"{"from":"Opened","event":"Kick","to":"Broken","hits":0}]}"#
+    ));
+
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["transitions"][0]["hits"], 2);
+}
@@ -0,0 +1,112 @@
+use static_fsm::{fsm, replay, Dispatch, Init, Table, Transition, Violation};
+
+pub struct Handle(u8);
+
+fsm!(door {
+    Init[Closed]
+    Memory[Opened(u32), Ajar(u32), Locked(u8, bool), Jammed(super::Handle)]
+    Open: Closed => Opened
+    Push: Opened => Ajar
+    Close: Opened, Ajar => Closed
+    Lock: Closed => Locked
+    Unlock: Locked => Closed
+    Jam: Opened => Jammed
+    Free: Jammed => Closed
+});
+
+fn closed() -> door::Variants {
+    door::FSM::<door::Closed>::init().to_enum()
+}
+
+fn opened(count: u32) -> door::Variants {
+    door::FSM::<door::Closed>::init().t(door::Open).to_enum(count)
+}
+
+#[test]
+fn rejects_events_without_transition() {
+    let result = closed().dispatch(door::Events::Close).map(|door| door.id());
+    assert_eq!(result.map_err(|door| door.id()), Err(door::StateId::Closed));
+}
+
+#[test]
+fn same_memory_moves_along() {
+    match opened(7).dispatch(door::Events::Push) {
+        Ok (door::Variants::Ajar(_, count)) => assert_eq!(count, 7),
+        _ => panic!("Push moves to Ajar")
+    }
+}
+
+#[test]
+fn other_memory_starts_as_default() {
+    match closed().dispatch(door::Events::Lock) {
+        Ok (door::Variants::Locked(_, code, engaged)) => assert_eq!((code, engaged), (0, false)),
+        _ => panic!("Lock moves to Locked")
+    }
+
+    assert_eq!(opened(7).dispatch(door::Events::Close).map(|door| door.id()).ok(), Some (door::StateId::Closed));
+}
+
+#[test]
+fn rejects_entering_memory_without_default() {
+    match opened(7).dispatch(door::Events::Jam) {
+        Err (door::Variants::Opened(_, count)) => assert_eq!(count, 7),
+        _ => panic!("Jam is rejected")
+    }
+
+    let jammed = door::FSM::<door::Closed>::init().t(door::Open).t(door::Jam).to_enum(Handle(1));
+    match &jammed {
+        door::Variants::Jammed(_, handle) => assert_eq!(handle.0, 1),
+        _ => panic!("the typestate API enters Jammed")
+    }
+    assert_eq!(jammed.dispatch(door::Events::Free).map(|door| door.id()).ok(), Some (door::StateId::Closed));
+}
+
+#[test]
+fn implements_dispatch_for_the_whole_machine() {
+    fn run<M: Dispatch>(machine: M, events: &[M::Event]) -> M::Id {
+        events.iter().fold(machine, |machine, event| machine.dispatch(*event).unwrap_or_else(|machine| machine)).id()
+    }
+
+    assert_eq!(run(closed(), &[door::Events::Open, door::Events::Jam, door::Events::Push]), door::StateId::Ajar);
+    assert_eq!(<door::Variants as Dispatch>::NAME, "door");
+}
+
+// A machine in every state
+fn every_state() -> Vec<door::Variants> {
+    let closed = || door::FSM::<door::Closed>::init();
+
+    vec![
+        closed().to_enum(),
+        closed().t(door::Open).to_enum(7),
+        closed().t(door::Open).t(door::Push).to_enum(7),
+        closed().t(door::Lock).to_enum(3, true),
+        closed().t(door::Open).t(door::Jam).to_enum(Handle(1))
+    ]
+}
+
+#[test]
+fn next_agrees_with_dispatch_on_every_edge() {
+    assert_eq!(every_state().len(), door::StateId::ALL.len());
+
+    for event in door::Events::ALL {
+        for machine in every_state() {
+            let id = machine.id();
+            let next = id.next(event);
+
+            assert_eq!(<door::StateId as Table>::next(id, event), next);
+            assert_eq!(<door::Variants as Dispatch>::next(id, event), next);
+            assert_eq!(machine.dispatch(event).map(|machine| machine.id()).ok(), next, "{:?} on {:?}", id, event);
+        }
+    }
+
+    assert_eq!(door::StateId::Opened.next(door::Events::Jam), None);
+    assert_eq!(door::StateId::Jammed.next(door::Events::Free), Some (door::StateId::Closed));
+}
+
+#[test]
+fn the_table_leaves_out_transitions_dispatch_rejects() {
+    let err = replay(door::StateId::Closed, "Open\nJam").unwrap_err();
+
+    assert_eq!(err.line, 2);
+    assert_eq!(err.violation, Violation::Rejected { state: door::StateId::Opened, event: door::Events::Jam });
+}
@@ -0,0 +1,23 @@
+#![cfg(feature = "async")]
+
+use futures::{executor::block_on, stream, StreamExt};
+use static_fsm::{Change, Driver};
+
+mod common;
+
+use common::door::{self, closed};
+
+#[test]
+fn drives_the_machine_with_a_stream() {
+    let events = stream::iter([door::Events::Open, door::Events::Open, door::Events::Close]);
+    let mut driver = Driver::new(closed(), events);
+
+    let changes: Vec<Change<door::StateId, door::Events>> = block_on((&mut driver).collect());
+
+    assert_eq!(changes, [
+        Change::Moved { from: door::StateId::Closed, event: door::Events::Open, to: door::StateId::Opened },
+        Change::Rejected { state: door::StateId::Opened, event: door::Events::Open },
+        Change::Moved { from: door::StateId::Opened, event: door::Events::Close, to: door::StateId::Closed }
+    ]);
+    assert_eq!(driver.machine().id(), door::StateId::Closed);
+}
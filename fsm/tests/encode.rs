@@ -0,0 +1,75 @@
+use static_fsm::{fsm, Encode, EncodeError, Init, Transition};
+
+fsm!(door {
+    Init[Closed]
+    Memory[Opened(u32, Option<char>, [bool; 2])]
+    Open: Closed => Opened
+    Close: Opened => Closed
+});
+
+fsm!(#[repr(u8)] small {
+    Init[Closed]
+    Open: Closed => Opened
+    Close: Opened => Closed
+});
+
+fn opened() -> door::Variants {
+    door::FSM::<door::Closed>::init().t(door::Open).to_enum(7, Some ('x'), [true, false])
+}
+
+#[test]
+fn snapshots_round_trip_with_their_memory() {
+    let mut buf = [0; 16];
+    let len = opened().encode(&mut buf).unwrap();
+
+    assert_eq!(&buf[..len], [1, 0, 7, 0, 0, 0, 1, b'x', 0, 0, 0, 1, 0]);
+
+    match door::Variants::decode(&buf[..len]).unwrap() {
+        door::Variants::Opened(_, count, key, flags) => assert_eq!((count, key, flags), (7, Some ('x'), [true, false])),
+        _ => panic!("decoded the wrong state")
+    }
+}
+
+#[test]
+fn u8_machines_write_one_byte_ids() {
+    let mut buf = [0; 4];
+    let len = small::FSM::<small::Closed>::init().t(small::Open).to_enum().encode(&mut buf).unwrap();
+
+    assert_eq!(&buf[..len], [1]);
+    assert_eq!(small::Variants::decode(&buf[..len]).unwrap().id(), small::StateId::Opened);
+}
+
+#[test]
+fn reports_a_full_buffer() {
+    assert_eq!(opened().encode(&mut [0; 4]), Err(EncodeError::BufferFull));
+    assert_eq!(opened().encode(&mut []), Err(EncodeError::BufferFull));
+}
+
+#[test]
+fn reports_a_truncated_snapshot() {
+    assert_eq!(door::Variants::decode(&[1]).map(|variant| variant.id()), Err(EncodeError::Truncated));
+    assert_eq!(door::Variants::decode(&[1, 0, 7, 0]).map(|variant| variant.id()), Err(EncodeError::Truncated));
+}
+
+#[test]
+fn reports_unknown_states() {
+    assert_eq!(door::Variants::decode(&[9, 0]).map(|variant| variant.id()), Err(EncodeError::UnknownState(9)));
+}
+
+#[test]
+fn reports_invalid_values() {
+    assert_eq!(bool::decode(&[2]), Err(EncodeError::Invalid));
+    assert_eq!(char::decode(&0xD800u32.to_le_bytes()), Err(EncodeError::Invalid));
+    assert_eq!(<Option<u8>>::decode(&[2, 0]), Err(EncodeError::Invalid));
+}
+
+#[test]
+fn primitives_are_little_endian() {
+    let mut buf = [0; 8];
+
+    assert_eq!(0x0102_0304u32.encode(&mut buf), Ok (4));
+    assert_eq!(buf[..4], [4, 3, 2, 1]);
+    assert_eq!(u32::decode(&buf), Ok ((0x0102_0304, 4)));
+    assert_eq!(().encode(&mut buf), Ok (0));
+    assert_eq!(<[u8; 3]>::decode(&[1, 2, 3, 4]), Ok (([1, 2, 3], 3)));
+}
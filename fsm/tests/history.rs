@@ -0,0 +1,67 @@
+use static_fsm::{FakeClock, History, Runner};
+
+mod common;
+
+use common::door::{self, closed};
+
+fn toggle<O: static_fsm::Observer<door::Variants>>(runner: &mut Runner<door::Variants, 1, static_fsm::NoClock, O>, times: usize) {
+    for i in 0..times {
+        let event = if i % 2 == 0 { door::Events::Open } else { door::Events::Close };
+        runner.handle(event, |_, _, _| {}).unwrap();
+    }
+}
+
+#[test]
+fn keeps_the_last_transitions_oldest_first() {
+    let mut runner = Runner::<_, 1>::new(closed()).with_observer(History::<_, 2>::new());
+    toggle(&mut runner, 3);
+
+    let history = runner.observer();
+    let events: Vec<&str> = history.iter().map(|record| record.event.name).collect();
+
+    assert_eq!(history.len(), 2);
+    assert_eq!(events, ["Close", "Open"]);
+    assert_eq!(history.last().map(|record| record.to.id), Some (door::StateId::Opened));
+}
+
+#[test]
+fn ignores_rejected_events() {
+    let mut runner = Runner::<_, 1>::new(closed()).with_observer(History::<_, 4>::default());
+    runner.handle(door::Events::Close, |_, _, _| {}).unwrap();
+
+    assert!(runner.observer().is_empty());
+    assert_eq!(runner.observer().last(), None);
+}
+
+#[test]
+fn stamps_and_prints_the_transitions() {
+    let clock = FakeClock::new();
+    let mut runner = Runner::<_, 1>::new(closed()).with_observer(History::<_, 4, _>::with_clock(&clock));
+
+    clock.advance(100);
+    runner.handle(door::Events::Open, |_, _, _| {}).unwrap();
+    clock.advance(150);
+    runner.handle(door::Events::Close, |_, _, _| {}).unwrap();
+
+    assert_eq!(runner.observer().to_string(), "100: Closed --Open--> Opened\n250: Opened --Close--> Closed\n");
+}
+
+#[test]
+fn clears_the_records() {
+    let mut runner = Runner::<_, 1>::new(closed()).with_observer(History::<_, 2>::new());
+    toggle(&mut runner, 3);
+
+    runner.observer_mut().clear();
+    runner.handle(door::Events::Close, |_, _, _| {}).unwrap();
+
+    assert_eq!(runner.observer().to_string(), "0: Opened --Close--> Closed\n");
+}
+
+#[test]
+fn records_nothing_without_capacity() {
+    let mut runner = Runner::<_, 1>::new(closed()).with_observer(History::<_, 0>::new());
+    toggle(&mut runner, 2);
+
+    assert!(runner.observer().is_empty());
+    assert_eq!(runner.observer().iter().count(), 0);
+}
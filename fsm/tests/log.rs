@@ -0,0 +1,41 @@
+#![cfg(feature = "log")]
+
+use log::{Level, Log, Metadata, Record};
+use static_fsm::{LogObserver, Runner};
+
+use std::sync::Mutex;
+
+mod common;
+
+use common::door::{self, closed};
+
+struct Capture(Mutex<Vec<(Level, String)>>);
+
+impl Log for Capture {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        self.0.lock().unwrap().push((record.level(), record.args().to_string()));
+    }
+
+    fn flush(&self) { }
+}
+
+static CAPTURE: Capture = Capture(Mutex::new(Vec::new()));
+
+#[test]
+fn logs_transitions_and_rejected_events() {
+    log::set_logger(&CAPTURE).unwrap();
+    log::set_max_level(log::LevelFilter::Debug);
+
+    let mut runner = Runner::<_, 1>::new(closed()).with_observer(LogObserver);
+    runner.handle(door::Events::Open, |_, _, _| {}).unwrap();
+    runner.handle(door::Events::Open, |_, _, _| {}).unwrap();
+
+    assert_eq!(*CAPTURE.0.lock().unwrap(), [
+        (Level::Debug, "door: Closed --Open--> Opened".to_owned()),
+        (Level::Warn, "door: Opened has no transition for Open".to_owned())
+    ]);
+}
@@ -0,0 +1,64 @@
+use static_fsm::{fsm, EncodeError, Init, Transition};
+
+fsm!(#[version(2)] door {
+    Init[Closed]
+    Memory[Opened(u32, bool)]
+    Open: Closed => Opened
+    Close: Opened => Closed
+    Migrate[
+        0 { Closed, Opened (u8) },
+        #[repr(u8)]
+        1 { Idle => Closed, Opening (u16) => Opened, Opened (u8) }
+    ]
+});
+
+fsm!(#[version(1)] gate {
+    Init[Closed]
+    Memory[Opened(u8)]
+    Open: Closed => Opened
+    Close: Opened => Closed
+});
+
+fn opened(variant: door::Variants) -> (u32, bool) {
+    match variant {
+        door::Variants::Opened(_, count, locked) => (count, locked),
+        _ => panic!("decoded the wrong state")
+    }
+}
+
+#[test]
+fn current_snapshots_carry_the_version() {
+    let mut buf = [0; 16];
+    let len = door::FSM::<door::Closed>::init().t(door::Open).to_enum(7, true).encode(&mut buf).unwrap();
+
+    assert_eq!(&buf[..len], [2, 0, 1, 0, 7, 0, 0, 0, 1]);
+    assert_eq!(opened(door::Variants::decode(&buf[..len]).unwrap()), (7, true));
+}
+
+#[test]
+fn older_versions_use_their_own_layout() {
+    assert_eq!(opened(door::Variants::decode(&[1, 0, 1, 5, 1]).unwrap()), (261, false));
+    assert_eq!(opened(door::Variants::decode(&[1, 0, 2, 9]).unwrap()), (9, false));
+    assert_eq!(door::Variants::decode(&[1, 0, 0]).unwrap().id(), door::StateId::Closed);
+}
+
+#[test]
+fn headerless_snapshots_are_version_0() {
+    assert_eq!(opened(door::Variants::decode_unversioned(&[1, 0, 7]).unwrap()), (7, false));
+    assert_eq!(door::Variants::decode_unversioned(&[0, 0]).unwrap().id(), door::StateId::Closed);
+}
+
+#[test]
+fn headerless_snapshots_default_to_the_current_layout() {
+    match gate::Variants::decode_unversioned(&[1, 0, 4]).unwrap() {
+        gate::Variants::Opened(_, count) => assert_eq!(count, 4),
+        _ => panic!("decoded the wrong state")
+    }
+}
+
+#[test]
+fn reports_unknown_versions_and_states() {
+    assert_eq!(door::Variants::decode(&[5, 0, 0, 0]).map(|variant| variant.id()), Err(EncodeError::UnknownVersion(5)));
+    assert_eq!(door::Variants::decode(&[1, 0, 3]).map(|variant| variant.id()), Err(EncodeError::UnknownState(3)));
+    assert_eq!(door::Variants::decode_unversioned(&[2, 0]).map(|variant| variant.id()), Err(EncodeError::UnknownState(2)));
+}
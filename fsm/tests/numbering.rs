@@ -0,0 +1,52 @@
+use static_fsm::fsm;
+
+fsm!(door {
+    Init[Broken]
+    Memory[Opened(u8)]
+    Final[Locked]
+    Fix: Broken => Closed
+    Open: Closed => Opened
+    Close: Opened => Closed
+    Lock: Closed => Locked
+});
+
+fsm!(moved {
+    Fix: Broken => Closed
+    Open: Closed => Opened
+    Close: Opened => Closed
+    Lock: Closed => Locked
+    Final[Locked]
+    Memory[Opened(u8)]
+    Init[Broken]
+});
+
+fsm!(init_only {
+    Init[Done, Idle, Closed]
+    Final[Done]
+    Open: Closed => Opened
+    Close: Opened => Closed
+    Start: Idle => Closed
+});
+
+#[test]
+fn states_are_numbered_by_their_first_transition() {
+    assert_eq!(door::StateId::Broken.to_u16(), 0);
+    assert_eq!(door::StateId::Closed.to_u16(), 1);
+    assert_eq!(door::StateId::Opened.to_u16(), 2);
+    assert_eq!(door::StateId::Locked.to_u16(), 3);
+}
+
+#[test]
+fn moving_sections_keeps_the_numbers() {
+    let names = |ids: &[&str]| ids.join(",");
+
+    assert_eq!(
+        names(&door::StateId::ALL.map(door::StateId::name)),
+        names(&moved::StateId::ALL.map(moved::StateId::name))
+    );
+}
+
+#[test]
+fn transitions_come_before_other_sections() {
+    assert_eq!(init_only::StateId::ALL, [init_only::StateId::Closed, init_only::StateId::Opened, init_only::StateId::Idle, init_only::StateId::Done]);
+}
@@ -0,0 +1,63 @@
+use static_fsm::{Dispatch, Named, Observer, Runner};
+
+mod common;
+
+use common::door::{self, closed};
+
+#[derive(Default)]
+struct Recorder {
+    lines: Vec<String>
+}
+
+impl<M: Dispatch> Observer<M> for Recorder {
+    fn on_transition(&mut self, from: Named<M::Id>, event: Named<M::Event>, to: Named<M::Id>) {
+        self.lines.push(format!("{}: {} --{}--> {}", M::NAME, from.name, event.name, to.name));
+    }
+
+    fn on_rejected(&mut self, state: Named<M::Id>, event: Named<M::Event>) {
+        self.lines.push(format!("{}: {} rejects {}", M::NAME, state.name, event.name));
+    }
+}
+
+#[test]
+fn observes_transitions_and_rejected_events() {
+    let mut runner = Runner::<_, 2>::new(closed()).with_observer(Recorder::default());
+
+    runner.handle(door::Events::Open, |_, _, _| {}).unwrap();
+    runner.handle(door::Events::Open, |_, _, _| {}).unwrap();
+
+    assert_eq!(runner.observer().lines, [
+        "door: Closed --Open--> Opened",
+        "door: Opened rejects Open"
+    ]);
+}
+
+#[test]
+fn passes_ids_along_with_names() {
+    struct Ids(Vec<(door::StateId, door::Events, door::StateId)>);
+
+    impl Observer<door::Variants> for Ids {
+        fn on_transition(&mut self, from: Named<door::StateId>, event: Named<door::Events>, to: Named<door::StateId>) {
+            self.0.push((from.id, event.id, to.id));
+        }
+    }
+
+    let mut runner = Runner::<_, 1>::new(closed()).with_observer(Ids(Vec::new()));
+    runner.handle(door::Events::Open, |_, _, _| {}).unwrap();
+
+    assert_eq!(runner.observer().0, [(door::StateId::Closed, door::Events::Open, door::StateId::Opened)]);
+}
+
+#[test]
+fn combines_observers_as_tuples_and_borrows() {
+    let mut borrowed = Recorder::default();
+
+    {
+        let mut runner = Runner::<_, 1>::new(closed()).with_observer((Recorder::default(), &mut borrowed));
+        runner.handle(door::Events::Open, |_, _, _| {}).unwrap();
+
+        assert_eq!(runner.observer().0.lines, ["door: Closed --Open--> Opened"]);
+    }
+
+    assert_eq!(borrowed.lines, ["door: Closed --Open--> Opened"]);
+}
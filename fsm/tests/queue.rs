@@ -0,0 +1,91 @@
+use static_fsm::{fsm, Change, Init, Queue, Runner};
+
+fsm!(door {
+    Init[Closed]
+    Open: Closed => Opened
+    Close: Opened => Closed
+    Lock: Closed => Locked
+    Unlock: Locked => Closed
+});
+
+fn runner<const N: usize>() -> Runner<door::Variants, N> {
+    Runner::new(door::FSM::<door::Closed>::init().to_enum())
+}
+
+#[test]
+fn queues_in_fifo_order_across_the_wrap() {
+    let mut queue: Queue<u8, 2> = Queue::new();
+
+    assert_eq!(queue.capacity(), 2);
+    assert_eq!(queue.post(1), Ok (()));
+    assert_eq!(queue.post(2), Ok (()));
+    assert!(queue.is_full());
+    assert_eq!(queue.post(3), Err(3));
+    assert_eq!(queue.pop(), Some (1));
+    assert_eq!(queue.post(3), Ok (()));
+    assert_eq!(queue.pop(), Some (2));
+    assert_eq!(queue.pop(), Some (3));
+    assert_eq!(queue.pop(), None);
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn clears_the_queue() {
+    let mut queue: Queue<u8, 4> = Queue::default();
+    queue.post(1).unwrap();
+    queue.post(2).unwrap();
+
+    queue.clear();
+
+    assert_eq!(queue.len(), 0);
+    assert_eq!(queue.pop(), None);
+}
+
+#[test]
+fn runs_posted_events_to_completion_in_order() {
+    let mut runner = runner::<4>();
+    let mut seen: Vec<(door::StateId, door::StateId)> = Vec::new();
+
+    let processed = runner.handle(door::Events::Open, |door, change, queue| {
+        // Posted events wait until the current handler has returned
+        assert_eq!(door.id(), change.state());
+        if change.event() == door::Events::Open {
+            queue.post(door::Events::Close).unwrap();
+            queue.post(door::Events::Lock).unwrap();
+        }
+
+        if let Change::Moved { from, to, .. } = change {
+            seen.push((from, to));
+        }
+    });
+
+    assert_eq!(processed, Ok (3));
+    assert_eq!(seen, [
+        (door::StateId::Closed, door::StateId::Opened),
+        (door::StateId::Opened, door::StateId::Closed),
+        (door::StateId::Closed, door::StateId::Locked)
+    ]);
+    assert_eq!(runner.machine().id(), door::StateId::Locked);
+    assert!(runner.queue().is_empty());
+}
+
+#[test]
+fn reports_rejected_events_to_the_handler() {
+    let mut runner = runner::<1>();
+    let mut changes = Vec::new();
+
+    runner.handle(door::Events::Close, |_, change, _| changes.push(change)).unwrap();
+
+    assert_eq!(changes, [Change::Rejected { state: door::StateId::Closed, event: door::Events::Close }]);
+}
+
+#[test]
+fn gives_back_events_that_do_not_fit() {
+    let mut runner = runner::<1>();
+
+    assert_eq!(runner.post(door::Events::Open), Ok (()));
+    assert_eq!(runner.post(door::Events::Close), Err(door::Events::Close));
+    assert_eq!(runner.handle(door::Events::Close, |_, _, _| {}), Err(door::Events::Close));
+    assert_eq!(runner.run(|_, _, _| {}), 1);
+    assert_eq!(runner.into_inner().id(), door::StateId::Opened);
+}
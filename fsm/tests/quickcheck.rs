@@ -0,0 +1,62 @@
+#![cfg(feature = "quickcheck")]
+
+use quickcheck::{Arbitrary, Gen};
+use static_fsm::Walk;
+
+mod common;
+
+use common::{fragile, vault};
+
+#[test]
+fn walks_are_legal() {
+    fn property(walk: Walk<fragile::StateId>) -> bool {
+        walk.start == fragile::StateId::Closed && walk.is_legal()
+    }
+
+    quickcheck::quickcheck(property as fn(Walk<fragile::StateId>) -> bool);
+}
+
+#[test]
+fn walks_shrink_to_legal_prefixes() {
+    let mut g = Gen::new(32);
+
+    for _ in 0..64 {
+        let walk = Walk::<fragile::StateId>::arbitrary(&mut g);
+        let prefixes: Vec<Walk<fragile::StateId>> = walk.shrink().collect();
+
+        assert_eq!(prefixes.len(), walk.events.len());
+
+        for (len, prefix) in (0..walk.events.len()).rev().zip(prefixes) {
+            assert!(prefix.is_legal());
+            assert_eq!(prefix.start, walk.start);
+            assert_eq!(prefix.events, walk.events[..len]);
+        }
+    }
+}
+
+#[test]
+fn walks_without_init_start_anywhere() {
+    let mut g = Gen::new(4);
+    let mut starts = Vec::new();
+
+    for _ in 0..64 {
+        let walk = Walk::<vault::StateId>::arbitrary(&mut g);
+        assert!(walk.is_legal());
+
+        if !starts.contains(&walk.start) {
+            starts.push(walk.start);
+        }
+    }
+
+    starts.sort();
+    assert_eq!(starts, [vault::StateId::Closed, vault::StateId::Opened]);
+}
+
+#[test]
+fn events_are_any_event() {
+    let mut g = Gen::new(8);
+
+    for _ in 0..64 {
+        assert!(fragile::Events::ALL.contains(&fragile::Events::arbitrary(&mut g)));
+    }
+}
@@ -0,0 +1,68 @@
+use static_fsm::{fsm, replay, ReplayError, Violation};
+
+fsm!(door {
+    Init[Closed]
+    Open: Closed => Opened
+    Close: Opened => Closed
+    Lock: Closed => Locked
+    Unlock: Locked => Closed
+});
+
+use door::{Events, StateId};
+
+fn violation(log: &str) -> (usize, &str, Violation<'_, StateId>) {
+    let ReplayError { line, text, violation } = replay(StateId::Closed, log).unwrap_err();
+    (line, text, violation)
+}
+
+#[test]
+fn replays_a_legal_log() {
+    let log = "= Closed\nOpen => Opened\n\n  # closed by hand\nClose\nLock";
+
+    assert_eq!(replay(StateId::Closed, log), Ok (StateId::Locked));
+    assert_eq!(replay(StateId::Closed, ""), Ok (StateId::Closed));
+}
+
+#[test]
+fn reports_unknown_events() {
+    assert_eq!(violation("Open\nKick"), (2, "Kick", Violation::UnknownEvent("Kick")));
+}
+
+#[test]
+fn reports_unknown_states() {
+    assert_eq!(violation("= Closd"), (1, "= Closd", Violation::UnknownState("Closd")));
+    assert_eq!(violation("Open => Ajar"), (1, "Open => Ajar", Violation::UnknownState("Ajar")));
+}
+
+#[test]
+fn reports_rejected_events() {
+    assert_eq!(violation("Close"), (1, "Close", Violation::Rejected { state: StateId::Closed, event: Events::Close }));
+}
+
+#[test]
+fn reports_mismatched_states() {
+    assert_eq!(violation("Open\n= Closed"), (2, "= Closed", Violation::Mismatch { expected: StateId::Closed, actual: StateId::Opened }));
+    assert_eq!(violation("Lock => Opened"), (1, "Lock => Opened", Violation::Mismatch { expected: StateId::Opened, actual: StateId::Locked }));
+}
+
+#[test]
+fn reports_an_arrow_without_event() {
+    assert_eq!(violation("=> Closed"), (1, "=> Closed", Violation::MissingEvent));
+    assert_eq!(violation("Open\n  => Opened"), (2, "=> Opened", Violation::MissingEvent));
+}
+
+#[test]
+fn numbers_lines_including_blanks_and_comments() {
+    assert_eq!(violation("\n# start\n\nOpen\nOpen").0, 5);
+}
+
+#[test]
+fn prints_the_line_and_the_violation() {
+    let message = |log| replay(StateId::Closed, log).unwrap_err().to_string();
+
+    assert_eq!(message("Kick"), "line 1: `Kick`: unknown event Kick");
+    assert_eq!(message("= Ajar"), "line 1: `= Ajar`: unknown state Ajar");
+    assert_eq!(message("=> Closed"), "line 1: `=> Closed`: missing event before `=>`");
+    assert_eq!(message("Close"), "line 1: `Close`: Closed has no transition for Close");
+    assert_eq!(message("= Opened"), "line 1: `= Opened`: expected state Opened, but the machine is in Closed");
+}
@@ -0,0 +1,29 @@
+use static_fsm::fsm;
+
+fsm!(#[repr(u8)] door {
+    Init[Closed]
+    Open: Closed => Opened
+    Close: Opened => Closed
+});
+
+fsm!(wide {
+    Init[Closed]
+    Open: Closed => Opened
+    Close: Opened => Closed
+});
+
+#[test]
+fn u8_machines_convert_to_both_widths() {
+    assert_eq!(door::StateId::Opened.to_u8(), 1);
+    assert_eq!(door::StateId::Opened.to_u16(), 1);
+    assert_eq!(door::StateId::from_u8(1), Some (door::StateId::Opened));
+    assert_eq!(door::StateId::from_u16(2), None);
+    assert_eq!(core::mem::size_of::<door::StateId>(), 1);
+}
+
+#[test]
+fn u16_machines_convert_to_u16() {
+    assert_eq!(wide::StateId::Opened.to_u16(), 1);
+    assert_eq!(wide::StateId::from_u8(1), Some (wide::StateId::Opened));
+    assert_eq!(core::mem::size_of::<wide::StateId>(), 2);
+}
@@ -0,0 +1,70 @@
+use static_fsm::{fsm_scxml, Init, Transition};
+
+fsm_scxml!("tests/door.scxml");
+
+#[test]
+fn transitions_follow_the_document() {
+    let door = door::FSM::<door::Closed>::init();
+    let door = door.t(door::Open).t(door::Close).t(door::Lock);
+    let _: door::FSM<door::Broken> = door.t(door::Break);
+}
+
+#[test]
+fn initial_and_final_states() {
+    fn entry<S: static_fsm::State + static_fsm::EntryPoint>() { }
+    fn exit<S: static_fsm::State + static_fsm::ExitPoint>() { }
+
+    entry::<door::Closed>();
+    exit::<door::Broken>();
+}
+
+// Exported from `original::numbered` by `static-fsm scxml`
+fsm_scxml!("tests/numbered.scxml");
+
+// The generated modules are private, so the original is compared from inside
+mod original {
+    use static_fsm::{fsm, Init, Transition};
+
+    use super::numbered as exported;
+
+    fsm!(#[repr(u8)] #[version(2)] numbered {
+        Init[P, R]
+        Memory[Q (u32)]
+        Final[S]
+        E1: P => Q
+        E2: R => P
+        E3: Q => S
+        Migrate[
+            #[repr(u8)]
+            1 { Start => P, Middle (u16) => Q, R }
+        ]
+    });
+
+    #[test]
+    fn exported_machines_keep_their_numbers() {
+        let states: Vec<(&str, u8)> = exported::StateId::ALL.iter().map(|state| (state.name(), state.to_u8())).collect();
+        let original: Vec<(&str, u8)> = numbered::StateId::ALL.iter().map(|state| (state.name(), state.to_u8())).collect();
+        assert_eq!(states, original);
+
+        let events: Vec<(&str, u8)> = exported::Events::ALL.iter().map(|event| (event.name(), *event as u8)).collect();
+        let original: Vec<(&str, u8)> = numbered::Events::ALL.iter().map(|event| (event.name(), *event as u8)).collect();
+        assert_eq!(events, original);
+    }
+
+    #[test]
+    fn exported_machines_read_the_snapshots_of_the_original() {
+        let mut buf = [0; 8];
+        let len = numbered::FSM::<numbered::P>::init().t(numbered::E1).to_enum(7).encode(&mut buf).unwrap();
+
+        match exported::Variants::decode(&buf[..len]).unwrap() {
+            exported::Variants::Q(_, count) => assert_eq!(count, 7),
+            _ => panic!("decoded the wrong state")
+        }
+
+        // Version 1, Middle with 5
+        match exported::Variants::decode(&[1, 0, 1, 5, 0]).unwrap() {
+            exported::Variants::Q(_, count) => assert_eq!(count, 5),
+            _ => panic!("decoded the wrong state")
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use static_fsm::{fsm, Init, Table, Transition};
+
+fsm!(door {
+    Open: Closed => Opened
+    Final[Broken]
+    Close: Opened => Closed
+    Init[Closed]
+    Kick: Opened => Broken
+    Init[Opened]
+});
+
+mod common;
+
+use common::vault;
+
+#[test]
+fn sections_in_any_order_and_repeated() {
+    let door = door::FSM::<door::Opened>::init().t(door::Close).t(door::Open);
+    let _: door::FSM<door::Broken> = door.t(door::Kick);
+
+    assert_eq!(<door::StateId as Table>::INITS, [door::StateId::Closed, door::StateId::Opened]);
+}
+
+#[test]
+fn machines_without_init_are_only_restored() {
+    assert!(<vault::StateId as Table>::INITS.is_empty());
+
+    let vault = vault::Variants::decode(&vault::StateId::Opened.to_u16().to_le_bytes()).unwrap();
+    assert_eq!(vault.id(), vault::StateId::Opened);
+}
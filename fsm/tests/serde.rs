@@ -0,0 +1,30 @@
+#![cfg(feature = "serde")]
+
+use static_fsm::{fsm, Init};
+
+fsm!(door {
+    Init[Closed]
+    Memory[Opened(u8)]
+    Open: Closed => Opened
+    Close: Opened => Closed
+});
+
+#[test]
+fn states_and_events_serialize_by_name() {
+    assert_eq!(serde_json::to_string(&door::Closed).unwrap(), r#""Closed""#);
+    assert_eq!(serde_json::to_string(&door::Open).unwrap(), r#""Open""#);
+    assert_eq!(serde_json::to_string(&door::StateId::Opened).unwrap(), r#""Opened""#);
+
+    let _: door::Open = serde_json::from_str(r#""Open""#).unwrap();
+    assert!(serde_json::from_str::<door::Open>(r#""Close""#).is_err());
+}
+
+#[test]
+fn variants_round_trip_with_their_memory() {
+    let closed = door::FSM::<door::Closed>::init().to_enum();
+    assert_eq!(serde_json::to_string(&closed).unwrap(), r#""Closed""#);
+
+    let opened: door::Variants = serde_json::from_str(r#"{"Opened":3}"#).unwrap();
+    assert_eq!(opened.id(), door::StateId::Opened);
+    assert_eq!(serde_json::to_string(&opened).unwrap(), r#"{"Opened":3}"#);
+}
@@ -0,0 +1,131 @@
+use static_fsm::{fsm, Change, FakeClock, Init, Runner};
+
+fsm!(link {
+    Init[Idle]
+    Connect: Idle => Connecting
+    Connected: Connecting => Online
+    Timeout after 500: Connecting => Failed
+    Retry after 100: Failed => Connecting
+    Drop: Online => Idle
+});
+
+fn runner(clock: &FakeClock) -> Runner<link::Variants, 2, &FakeClock> {
+    Runner::with_clock(link::FSM::<link::Idle>::init().to_enum(), clock)
+}
+
+#[test]
+fn lists_the_timers_of_each_state() {
+    assert_eq!(link::StateId::Connecting.timers(), [(link::Events::Timeout, 500)]);
+    assert!(link::StateId::Idle.timers().is_empty());
+}
+
+#[test]
+fn fires_a_timer_once_its_delay_elapsed() {
+    let clock = FakeClock::new();
+    let mut runner = runner(&clock);
+
+    assert_eq!(runner.deadline(), None);
+    runner.handle(link::Events::Connect, |_, _, _| {}).unwrap();
+    assert_eq!(runner.deadline(), Some (500));
+
+    clock.advance(499);
+    assert_eq!(runner.poll(|_, _, _| {}), 0);
+    assert_eq!(runner.machine().id(), link::StateId::Connecting);
+
+    clock.advance(1);
+    assert_eq!(runner.poll(|_, _, _| {}), 1);
+    assert_eq!(runner.machine().id(), link::StateId::Failed);
+}
+
+#[test]
+fn states_entered_by_a_timer_start_at_its_deadline() {
+    let clock = FakeClock::new();
+    let mut runner = runner(&clock);
+    runner.handle(link::Events::Connect, |_, _, _| {}).unwrap();
+
+    clock.advance(700);
+    runner.poll(|_, _, _| {});
+    assert_eq!(runner.deadline(), Some (600));
+
+    assert_eq!(runner.poll(|_, _, _| {}), 1);
+    assert_eq!(runner.machine().id(), link::StateId::Connecting);
+    assert_eq!(runner.deadline(), Some (1100));
+}
+
+#[test]
+fn dispatches_the_timer_before_queued_events() {
+    let clock = FakeClock::new();
+    let mut runner = runner(&clock);
+    runner.handle(link::Events::Connect, |_, _, _| {}).unwrap();
+
+    clock.advance(500);
+    runner.post(link::Events::Connected).unwrap();
+    runner.post(link::Events::Drop).unwrap();
+
+    let mut changes = Vec::new();
+    assert_eq!(runner.poll(|_, change, _| changes.push(change)), 3);
+
+    assert_eq!(changes, [
+        Change::Moved { from: link::StateId::Connecting, event: link::Events::Timeout, to: link::StateId::Failed },
+        Change::Rejected { state: link::StateId::Failed, event: link::Events::Connected },
+        Change::Rejected { state: link::StateId::Failed, event: link::Events::Drop }
+    ]);
+}
+
+#[test]
+fn fires_timers_even_with_a_full_queue() {
+    let clock = FakeClock::new();
+    let mut runner = runner(&clock);
+    runner.handle(link::Events::Connect, |_, _, _| {}).unwrap();
+
+    clock.advance(500);
+    runner.post(link::Events::Drop).unwrap();
+    runner.post(link::Events::Drop).unwrap();
+
+    assert_eq!(runner.poll(|_, _, _| {}), 3);
+    assert_eq!(runner.machine().id(), link::StateId::Failed);
+}
+
+#[test]
+fn queued_events_reset_the_timers() {
+    let clock = FakeClock::new();
+    let mut runner = runner(&clock);
+    runner.handle(link::Events::Connect, |_, _, _| {}).unwrap();
+
+    clock.advance(300);
+    runner.handle(link::Events::Connected, |_, _, _| {}).unwrap();
+    clock.advance(300);
+
+    assert_eq!(runner.poll(|_, _, _| {}), 0);
+    assert_eq!(runner.machine().id(), link::StateId::Online);
+    assert_eq!(runner.deadline(), None);
+}
+
+pub struct Handle;
+
+fsm!(valve {
+    Init[Closed]
+    Memory[Stuck(super::Handle)]
+    Open: Closed => Opened
+    Stick after 10: Opened => Stuck
+    Close after 20: Opened => Closed
+    Free: Stuck => Closed
+});
+
+#[test]
+fn skips_timers_dispatch_would_reject() {
+    let clock = FakeClock::new();
+    let mut runner = Runner::<_, 1, _>::with_clock(valve::FSM::<valve::Closed>::init().to_enum(), &clock);
+    let mut changes = Vec::new();
+
+    runner.handle(valve::Events::Open, |_, _, _| {}).unwrap();
+    assert_eq!(valve::StateId::Opened.timers(), [(valve::Events::Stick, 10), (valve::Events::Close, 20)]);
+    assert_eq!(runner.deadline(), Some (20));
+
+    clock.advance(10);
+    assert_eq!(runner.poll(|_, change, _| changes.push(change)), 0);
+
+    clock.advance(10);
+    assert_eq!(runner.poll(|_, change, _| changes.push(change)), 1);
+    assert_eq!(changes, [Change::Moved { from: valve::StateId::Opened, event: valve::Events::Close, to: valve::StateId::Closed }]);
+}
@@ -0,0 +1,15 @@
+use static_fsm::{assert_no_transition, assert_transition, fsm};
+
+fsm!(door {
+    Init[Closed]
+    Open: Closed => Opened
+    Close: Opened => Closed
+    Lock: Closed => Locked
+    Unlock: Locked => Closed
+});
+
+assert_transition!(door: Closed --Open--> Opened);
+assert_no_transition!(door: Locked --Open);
+assert_no_transition!(door: Closed --Open);
+
+fn main() { }
@@ -0,0 +1,14 @@
+use static_fsm::{assert_no_transition, assert_transition, fsm};
+
+fsm!(door {
+    Init[Closed]
+    Open: Closed => Opened
+    Close: Opened => Closed
+    Lock: Closed => Locked
+    Unlock: Locked => Closed
+});
+
+assert_no_transition!(door: Locked --Open);
+assert_transition!(door: Closed --Lock--> Opened);
+
+fn main() { }
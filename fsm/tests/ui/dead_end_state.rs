@@ -0,0 +1,10 @@
+use static_fsm::fsm;
+
+fsm!(door {
+    Init[Closed]
+    Open: Closed => Opened
+    Close: Opened => Closed
+    Kick: Opened => Broken
+});
+
+fn main() { }
@@ -0,0 +1,5 @@
+use static_fsm::fsm;
+
+fsm!(empty { });
+
+fn main() { }
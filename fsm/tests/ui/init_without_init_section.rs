@@ -0,0 +1,10 @@
+use static_fsm::{fsm, Init};
+
+fsm!(vault {
+    Open: Closed => Opened
+    Close: Opened => Closed
+});
+
+fn main() {
+    let _ = vault::FSM::<vault::Closed>::init();
+}
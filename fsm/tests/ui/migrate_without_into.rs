@@ -0,0 +1,27 @@
+use static_fsm::{fsm, Encode, EncodeError};
+
+struct Count(u32);
+
+struct Legacy(u8);
+
+impl Encode for Legacy {
+    fn encode(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        self.0.encode(buf)
+    }
+
+    fn decode(buf: &[u8]) -> Result<(Self, usize), EncodeError> {
+        u8::decode(buf).map(|(value, len)| (Legacy(value), len))
+    }
+}
+
+fsm!(#[version(2)] door {
+    Init[Closed]
+    Memory[Opened(super::Count)]
+    Open: Closed => Opened
+    Close: Opened => Closed
+    Migrate[
+        1 { Closed, Opened (super::Legacy) }
+    ]
+});
+
+fn main() { }
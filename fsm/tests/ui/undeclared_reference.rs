@@ -0,0 +1,11 @@
+use static_fsm::fsm;
+
+fsm!(door {
+    Init[Closed]
+    Memory[Opend (u32)]
+    Trace[Closd]
+    Open: Closed => Opened
+    Close: Opened => Closed
+});
+
+fn main() { }
@@ -0,0 +1,9 @@
+use static_fsm::fsm;
+
+fsm!(door {
+    Inti[Closed]
+    Open: Closed => Opened
+    Close: Opened => Closed
+});
+
+fn main() { }
@@ -0,0 +1,12 @@
+use static_fsm::fsm;
+
+fsm!(door {
+    Init[Closed]
+    Final[Broken]
+    Open: Closed => Opened
+    Close: Opened => Closed
+    Kick: Closed, Locked => Broken
+    Unlock: Locked => Closed
+});
+
+fn main() { }
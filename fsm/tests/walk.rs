@@ -0,0 +1,106 @@
+#![cfg(feature = "proptest")]
+
+use proptest::{
+    prelude::*,
+    strategy::ValueTree,
+    test_runner::{TestError, TestRunner}
+};
+use static_fsm::{walk, walks, Walk};
+
+mod common;
+
+use common::{fragile, vault};
+
+proptest! {
+    #[test]
+    fn walks_are_legal(walk in walks::<fragile::StateId>(0..32)) {
+        prop_assert!(walk.is_legal());
+        prop_assert_eq!(walk.start, fragile::StateId::Closed);
+        prop_assert_eq!(walk.steps().count(), walk.events.len());
+    }
+
+    #[test]
+    fn walks_from_a_state_are_legal(walk in walk(fragile::StateId::Opened, 0..32)) {
+        prop_assert!(walk.is_legal());
+        prop_assert_eq!(walk.start, fragile::StateId::Opened);
+    }
+
+    #[test]
+    fn walks_without_init_start_anywhere(walk in walks::<vault::StateId>(0..8)) {
+        prop_assert!(walk.is_legal());
+    }
+
+    #[test]
+    fn events_are_any_event(event in any::<fragile::Events>()) {
+        prop_assert!(fragile::Events::ALL.contains(&event));
+    }
+}
+
+#[test]
+fn walks_end_early_in_states_without_transitions() {
+    let walk = Walk::new(fragile::StateId::Closed, [0, 1, 0, 0]);
+
+    assert_eq!(walk.to_string(), "Closed --Open--> Opened --Kick--> Broken");
+    assert_eq!(walk.end(), fragile::StateId::Broken);
+}
+
+#[test]
+fn walks_without_init_cover_every_state() {
+    let mut runner = TestRunner::deterministic();
+    let strategy = walks::<vault::StateId>(0..4);
+    let mut starts = Vec::new();
+
+    for _ in 0..64 {
+        let start = strategy.new_tree(&mut runner).unwrap().current().start;
+        if !starts.contains(&start) {
+            starts.push(start);
+        }
+    }
+
+    starts.sort();
+    assert_eq!(starts, [vault::StateId::Closed, vault::StateId::Opened]);
+}
+
+#[test]
+fn shrinking_stays_legal() {
+    let mut runner = TestRunner::deterministic();
+    let mut tree = walks::<fragile::StateId>(8..32).new_tree(&mut runner).unwrap();
+
+    assert!(tree.current().is_legal());
+
+    while tree.simplify() {
+        assert!(tree.current().is_legal());
+    }
+
+    while tree.complicate() {
+        assert!(tree.current().is_legal());
+    }
+}
+
+#[test]
+fn failures_shrink_to_legal_walks() {
+    let mut runner = TestRunner::deterministic();
+    let result = runner.run(&walks::<fragile::StateId>(0..32), |walk| {
+        prop_assert_ne!(walk.end(), fragile::StateId::Broken);
+        Ok (())
+    });
+
+    match result {
+        Err(TestError::Fail(_, walk)) => {
+            assert!(walk.is_legal());
+            assert_eq!(walk.end(), fragile::StateId::Broken);
+            assert_eq!(walk.to_string(), "Closed --Open--> Opened --Kick--> Broken");
+        },
+        _ => panic!("no walk reaches Broken")
+    }
+}
+
+#[test]
+fn walks_with_an_event_without_transition_are_illegal() {
+    let walk = |events: Vec<fragile::Events>| Walk { start: fragile::StateId::Closed, events };
+
+    assert!(walk(vec![]).is_legal());
+    assert!(walk(vec![fragile::Events::Open, fragile::Events::Kick]).is_legal());
+    assert!(!walk(vec![fragile::Events::Close]).is_legal());
+    assert!(!walk(vec![fragile::Events::Open, fragile::Events::Kick, fragile::Events::Close]).is_legal());
+}
@@ -0,0 +1,133 @@
+//! Build script code generation for `static-fsm`.
+//!
+//! Expanding large machines through the `fsm!` proc macro is slow in IDEs and
+//! hard to inspect. This crate generates the same code from a standalone
+//! machine definition at build time and writes it, pretty-printed, into
+//! `OUT_DIR`:
+//!
+//! ```ignore
+//! // build.rs
+//! fn main() {
+//!     let out_dir = std::env::var("OUT_DIR").unwrap();
+//!     static_fsm_build::generate("door.fsm", out_dir).unwrap_or_else(|err| panic!("{}", err));
+//! }
+//!
+//! // src/lib.rs
+//! include!(concat!(env!("OUT_DIR"), "/door.rs"));
+//! ```
+//!
+//! A `.fsm` file contains exactly what would otherwise be passed to `fsm!`,
+//! i.e. the machine name followed by a braced block of sections.
+//!
+//! The serde and `Arbitrary` implementations follow the features of the
+//! `static-fsm` dependency of the crate including the generated code.
+
+use fsm_model::{
+    Errors,
+    Machine
+};
+use quote::quote;
+
+use std::fmt::{
+    self,
+    Display
+};
+use std::path::{
+    Path,
+    PathBuf
+};
+use std::fs;
+use std::io;
+
+/// An error raised while generating a machine.
+#[derive(Debug)]
+pub enum Error {
+    /// Reading the definition or writing the generated code failed.
+    Io(PathBuf, io::Error),
+    /// The definition is not a valid machine.
+    Parse(PathBuf, syn::Error)
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(path, err) => write!(f, "{}: {}", path.display(), err),
+            Error::Parse(path, err) => write!(f, "{}", Errors::new(path, err))
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(_, err) => Some (err),
+            Error::Parse(_, err) => Some (err)
+        }
+    }
+}
+
+/// Expand a machine definition into pretty-printed Rust source.
+///
+/// The output is the code `fsm!` generates for the same definition.
+pub fn expand(src: &str) -> syn::Result<String> {
+    let machine = Machine::parse_str(src)?;
+    machine.validate()?;
+
+    unparse(&machine)
+}
+
+/// Generate the code of a parsed machine as pretty-printed Rust source.
+pub fn unparse(machine: &Machine) -> syn::Result<String> {
+    let file: syn::File = syn::parse2(quote!(#machine))?;
+
+    Ok (prettyplease::unparse(&file))
+}
+
+/// Generate the code of the machine defined in `src` into `out_dir`.
+///
+/// The code is written to `out_dir/<stem>.rs`, where `<stem>` is the file
+/// stem of `src`, and the path of that file is returned. Cargo is told to
+/// rerun the build script when `src` changes, and warnings about the
+/// machine are forwarded to Cargo.
+pub fn generate<P, Q>(src: P, out_dir: Q) -> Result<PathBuf, Error>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>
+{
+    let src = src.as_ref();
+
+    println!("cargo:rerun-if-changed={}", src.display());
+
+    let text = fs::read_to_string(src)
+        .map_err(|err| Error::Io(src.to_owned(), err))?;
+    let machine = Machine::parse_str(&text)
+        .and_then(|machine| machine.validate().map(|_| machine))
+        .map_err(|err| Error::Parse(src.to_owned(), err))?;
+
+    // A directive ends at the end of its line, so every line of a warning,
+    // e.g. its help, gets its own
+    for warning in machine.warnings() {
+        let start = warning.span.start();
+        let mut lines = warning.message.lines();
+
+        if let Some(first) = lines.next() {
+            println!("cargo:warning={}:{}:{}: {}", src.display(), start.line, start.column + 1, first);
+        }
+
+        for line in lines {
+            println!("cargo:warning={}", line);
+        }
+    }
+
+    let code = unparse(&machine)
+        .map_err(|err| Error::Parse(src.to_owned(), err))?;
+
+    let stem = src.file_stem().unwrap_or(src.as_os_str()).to_string_lossy();
+    let dest = out_dir.as_ref().join(format!{"{}.rs", stem});
+
+    let code = format!{"// Generated by static-fsm-build from {}. Do not edit.\n\n{}", src.display(), code};
+    fs::write(&dest, code)
+        .map_err(|err| Error::Io(dest.clone(), err))?;
+
+    Ok (dest)
+}
@@ -0,0 +1,92 @@
+use std::fs;
+use std::path::PathBuf;
+
+const DOOR: &str = "door {
+    Init[Closed]
+    Open: Closed => Opened
+    Close: Opened => Closed
+}
+";
+
+fn dir(name: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn expands_to_the_code_of_fsm() {
+    let code = static_fsm_build::expand(DOOR).unwrap();
+
+    assert!(code.starts_with("#[allow(non_snake_case)]\nmod door {\n"));
+    assert!(code.contains("impl Transition<Open> for FSM<Closed> {"));
+    syn::parse_file(&code).unwrap();
+}
+
+#[test]
+fn generates_into_the_output_directory() {
+    let dir = dir("generate");
+    let src = dir.join("door.fsm");
+    fs::write(&src, DOOR).unwrap();
+
+    let dest = static_fsm_build::generate(&src, &dir).unwrap();
+
+    assert_eq!(dest, dir.join("door.rs"));
+    let code = fs::read_to_string(dest).unwrap();
+    assert!(code.starts_with(&format!{"// Generated by static-fsm-build from {}. Do not edit.\n\n", src.display()}));
+    assert!(code.contains("mod door {"));
+}
+
+#[test]
+fn reports_errors_at_their_position() {
+    let dir = dir("errors");
+    let src = dir.join("door.fsm");
+    fs::write(&src, "door {\n    Init[Closed]\n    Open: Closed => Opened\n    Open: Opened => Closed\n}\n").unwrap();
+
+    let err = static_fsm_build::generate(&src, &dir).unwrap_err();
+
+    assert_eq!(err.to_string(), format!{"{0}:4:5: Duplicate event: Open\n{0}:3:5: First declared here", src.display()});
+}
+
+#[test]
+fn reports_missing_files() {
+    let dir = dir("missing");
+    let err = static_fsm_build::generate(dir.join("door.fsm"), &dir).unwrap_err();
+
+    assert!(matches!(err, static_fsm_build::Error::Io(..)));
+}
+
+// Run by `prints_one_directive_per_warning_line` in a child process, whose
+// output is not captured
+#[test]
+fn generate_with_warnings() {
+    let Some(dir) = std::env::var_os("STATIC_FSM_BUILD_WARNINGS") else {
+        return;
+    };
+
+    let dir = PathBuf::from(dir);
+    static_fsm_build::generate(dir.join("door.fsm"), &dir).unwrap();
+}
+
+#[test]
+fn prints_one_directive_per_warning_line() {
+    let dir = dir("warnings");
+    let src = dir.join("door.fsm");
+    fs::write(&src, "door {\n    Init[Closed]\n    Open: Closed => Opened\n    Opne: Opened => Closed\n}\n").unwrap();
+
+    let output = std::process::Command::new(std::env::current_exe().unwrap())
+        .args(["--exact", "generate_with_warnings", "--nocapture", "--test-threads=1"])
+        .env("STATIC_FSM_BUILD_WARNINGS", &dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let directives: Vec<&str> = stdout.lines().filter(|line| line.starts_with("cargo:warning=")).collect();
+
+    assert_eq!(directives, [
+        format!{"cargo:warning={}:4:5: Event Opne is similar to event Open; did you mean `Open`?", src.display()},
+        "cargo:warning=  = help: allow with #[allow(similar_events)]".to_owned()
+    ]);
+}
@@ -0,0 +1,87 @@
+use fsm_model::Machine;
+
+use std::fmt::Write;
+use std::str::FromStr;
+
+/// A diagram format supported by `graph`.
+#[derive(Clone, Copy)]
+pub enum Format {
+    Dot,
+    Mermaid,
+    PlantUml
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dot" => Ok (Format::Dot),
+            "mermaid" => Ok (Format::Mermaid),
+            "plantuml" => Ok (Format::PlantUml),
+            other => Err(format!{"unknown graph format `{}`, expected dot, mermaid or plantuml", other})
+        }
+    }
+}
+
+/// Render the states and transitions of a machine as a diagram.
+pub fn render(machine: &Machine, format: Format) -> String {
+    let mut out = String::new();
+
+    let transitions = machine.events.iter()
+        .flat_map(|event| {
+            let label = match event.delay() {
+                Some (delay) => format!{"{} after {}", event.name, delay},
+                None => event.name.to_string()
+            };
+
+            event.transitions.iter().map(move |transition| (transition, label.clone()))
+        });
+
+    match format {
+        Format::Dot => {
+            let _ = writeln!(out, "digraph {} {{", machine.name);
+            let _ = writeln!(out, "    rankdir=LR;");
+            for state in machine.states.iter() {
+                let shape = if machine.exits.iter().any(|exit| exit.state == state.state) {
+                    "doublecircle"
+                } else {
+                    "circle"
+                };
+                let _ = writeln!(out, "    {} [shape={}];", state.state, shape);
+            }
+            for init in machine.inits.iter() {
+                let _ = writeln!(out, "    __init_{} [shape=point];", init.state);
+                let _ = writeln!(out, "    __init_{} -> {};", init.state, init.state);
+            }
+            for (transition, label) in transitions {
+                let _ = writeln!(out, "    {} -> {} [label=\"{}\"];", transition.prev, transition.next, label);
+            }
+            let _ = writeln!(out, "}}");
+        },
+        Format::Mermaid | Format::PlantUml => {
+            if let Format::Mermaid = format {
+                let _ = writeln!(out, "---");
+                let _ = writeln!(out, "title: {}", machine.name);
+                let _ = writeln!(out, "---");
+                let _ = writeln!(out, "stateDiagram-v2");
+            } else {
+                let _ = writeln!(out, "@startuml {}", machine.name);
+            }
+            for init in machine.inits.iter() {
+                let _ = writeln!(out, "    [*] --> {}", init.state);
+            }
+            for (transition, label) in transitions {
+                let _ = writeln!(out, "    {} --> {} : {}", transition.prev, transition.next, label);
+            }
+            for exit in machine.exits.iter() {
+                let _ = writeln!(out, "    {} --> [*]", exit.state);
+            }
+            if let Format::PlantUml = format {
+                let _ = writeln!(out, "@enduml");
+            }
+        }
+    }
+
+    out
+}
@@ -0,0 +1,135 @@
+//! Command line tool for validating and rendering `static-fsm` machines.
+
+mod graph;
+mod source;
+
+use crate::graph::Format;
+use crate::source::{
+    Error,
+    Source
+};
+
+use std::env;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: static-fsm <COMMAND> [OPTIONS] <FILE>...
+
+Commands:
+  check     Parse and validate the machines
+  graph     Render the machines as diagrams (--format dot|mermaid|plantuml)
+  stats     Count the states, events and transitions of the machines
+  expand    Print the Rust code generated for the machines
+  scxml     Print the machines as SCXML documents
+
+Files ending in .rs are searched for fsm! invocations, files ending in .scxml
+are imported as SCXML documents and any other file is read as a single
+machine definition.";
+
+enum Command {
+    Check,
+    Graph(Format),
+    Stats,
+    Expand,
+    Scxml
+}
+
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1);
+
+    let mut command = match args.next().as_deref() {
+        Some ("check") => Command::Check,
+        Some ("graph") => Command::Graph(Format::Dot),
+        Some ("stats") => Command::Stats,
+        Some ("expand") => Command::Expand,
+        Some ("scxml") => Command::Scxml,
+        Some ("-h" | "--help") => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        },
+        Some (other) => return usage(&format!{"unknown command `{}`", other}),
+        None => return usage("missing command")
+    };
+
+    let mut files: Vec<PathBuf> = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match (&mut command, arg.as_str()) {
+            (Command::Graph(format), "--format" | "-f") => {
+                let value = match args.next() {
+                    Some (value) => value,
+                    None => return usage("missing value for --format")
+                };
+
+                match value.parse() {
+                    Ok (value) => *format = value,
+                    Err (err) => return usage(&err)
+                }
+            },
+            (_, "-h" | "--help") => {
+                println!("{}", USAGE);
+                return ExitCode::SUCCESS;
+            },
+            (_, option) if option.starts_with('-') => return usage(&format!{"unknown option `{}`", option}),
+            (_, file) => files.push(file.into())
+        }
+    }
+
+    if files.is_empty() {
+        return usage("missing input file");
+    }
+
+    let mut status = ExitCode::SUCCESS;
+
+    for file in files {
+        if let Err(err) = run(&command, Source::load(&file)) {
+            eprintln!("error: {}", err);
+            status = ExitCode::FAILURE;
+        }
+    }
+
+    status
+}
+
+fn run(command: &Command, source: Result<Source, Error>) -> Result<(), Error> {
+    let source = source?;
+
+    for machine in source.machines.iter() {
+        match command {
+            Command::Check => {
+                machine.validate().map_err(|err| source.error(err))?;
+                for warning in machine.warnings() {
+                    eprintln!("warning: {}", source.warning(&warning));
+                }
+                println!("{}: {}: ok", source.path.display(), machine.name);
+            },
+            Command::Graph(format) => print!("{}", graph::render(machine, *format)),
+            Command::Stats => {
+                let transitions = machine.events.iter()
+                    .map(|event| event.transitions.iter().count())
+                    .sum::<usize>();
+
+                println!("{}: {}", source.path.display(), machine.name);
+                println!("    states:      {}", machine.states.iter().count());
+                println!("    initial:     {}", machine.inits.iter().count());
+                println!("    final:       {}", machine.exits.iter().count());
+                println!("    events:      {}", machine.events.iter().count());
+                println!("    transitions: {}", transitions);
+            },
+            Command::Expand => {
+                machine.validate().map_err(|err| source.error(err))?;
+                let code = static_fsm_build::unparse(machine).map_err(|err| source.error(err))?;
+                print!("{}", code);
+            },
+            Command::Scxml => print!("{}", machine.scxml())
+        }
+    }
+
+    Ok (())
+}
+
+fn usage(msg: &str) -> ExitCode {
+    eprintln!("error: {}\n\n{}", msg, USAGE);
+    ExitCode::FAILURE
+}
@@ -0,0 +1,141 @@
+use fsm_model::{
+    Errors,
+    Machine,
+    Warning
+};
+use proc_macro2::Span;
+use syn::visit::{
+    self,
+    Visit
+};
+
+use std::fmt::{
+    self,
+    Display
+};
+use std::path::{
+    Path,
+    PathBuf
+};
+use std::fs;
+use std::io;
+
+/// An error raised while loading or processing a source file.
+pub enum Error {
+    /// The file could not be read.
+    Io(PathBuf, io::Error),
+    /// The file contains an invalid machine, spans point into the file.
+    Parse(PathBuf, syn::Error),
+    /// The file contains an invalid machine, spans carry no position.
+    Invalid(PathBuf, syn::Error),
+    /// The file contains no machine.
+    Empty(PathBuf)
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(path, err) => write!(f, "{}: {}", path.display(), err),
+            Error::Parse(path, err) => write!(f, "{}", Errors::new(path, err)),
+            Error::Invalid(path, err) => write!(f, "{}", Errors::without_positions(path, err)),
+            Error::Empty(path) => write!(f, "{}: no machine definition found", path.display())
+        }
+    }
+}
+
+/// The machines defined in a source file.
+pub struct Source {
+    pub path: PathBuf,
+    pub machines: Vec<Machine>,
+    scxml: bool
+}
+
+impl Source {
+    /// Load the machines defined in `path`.
+    ///
+    /// `.rs` files are searched for `fsm!` invocations, `.scxml` files are
+    /// imported as SCXML documents and any other file is parsed as a single
+    /// machine definition.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let text = fs::read_to_string(path)
+            .map_err(|err| Error::Io(path.to_owned(), err))?;
+
+        let (machines, scxml) = match path.extension().and_then(|ext| ext.to_str()) {
+            Some ("rs") => (rust(&text), false),
+            Some ("scxml") => {
+                let machine = Machine::from_scxml(&text, &path.display().to_string(), Span::call_site())
+                    .map_err(|err| Error::Invalid(path.to_owned(), err))?;
+                (Ok (vec![machine]), true)
+            },
+            _ => (Machine::parse_str(&text).map(|machine| vec![machine]), false)
+        };
+
+        let machines = machines.map_err(|err| Error::Parse(path.to_owned(), err))?;
+
+        if machines.is_empty() {
+            return Err(Error::Empty(path.to_owned()));
+        }
+
+        Ok (Source {
+            path: path.to_owned(),
+            machines,
+            scxml
+        })
+    }
+
+    /// Format a warning about one of the machines.
+    pub fn warning(&self, warning: &Warning) -> String {
+        if self.scxml {
+            format!{"{}: {}", self.path.display(), warning.message}
+        } else {
+            let start = warning.span.start();
+            format!{"{}:{}:{}: {}", self.path.display(), start.line, start.column + 1, warning.message}
+        }
+    }
+
+    /// Attach an error raised while processing one of the machines to the file.
+    pub fn error(&self, err: syn::Error) -> Error {
+        if self.scxml {
+            Error::Invalid(self.path.clone(), err)
+        } else {
+            Error::Parse(self.path.clone(), err)
+        }
+    }
+}
+
+struct Invocations {
+    machines: Vec<Machine>,
+    errors: Vec<syn::Error>
+}
+
+impl<'ast> Visit<'ast> for Invocations {
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        if mac.path.segments.last().is_some_and(|segment| segment.ident == "fsm") {
+            match mac.parse_body::<Machine>() {
+                Ok (machine) => self.machines.push(machine),
+                Err (err) => self.errors.push(err)
+            }
+        }
+
+        visit::visit_macro(self, mac);
+    }
+}
+
+fn rust(text: &str) -> syn::Result<Vec<Machine>> {
+    let file = syn::parse_file(text)?;
+
+    let mut invocations = Invocations {
+        machines: Vec::new(),
+        errors: Vec::new()
+    };
+    invocations.visit_file(&file);
+
+    let mut errors = invocations.errors.into_iter();
+    match errors.next() {
+        Some (mut err) => {
+            errors.for_each(|other| err.combine(other));
+            Err(err)
+        },
+        None => Ok (invocations.machines)
+    }
+}
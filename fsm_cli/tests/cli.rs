@@ -0,0 +1,131 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::{
+    Command,
+    Output
+};
+
+const DOOR: &str = "door {
+    Init[Closed]
+    Final[Broken]
+    Open: Closed => Opened
+    Close: Opened => Closed
+    Kick { Closed, Opened => Broken }
+}
+";
+
+fn file(name: &str, text: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("cli");
+    fs::create_dir_all(&dir).unwrap();
+
+    let path = dir.join(name);
+    fs::write(&path, text).unwrap();
+    path
+}
+
+fn run(args: &[&str], path: &PathBuf) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_static-fsm"))
+        .args(args)
+        .arg(path)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+#[test]
+fn checks_valid_machines() {
+    let path = file("check.fsm", DOOR);
+    let output = run(&["check"], &path);
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), format!{"{}: door: ok\n", path.display()});
+}
+
+#[test]
+fn reports_invalid_machines_at_their_position() {
+    let path = file("invalid.fsm", "door {\n    Init[Closed]\n    Open: Closed => Opened\n}\n");
+    let output = run(&["check"], &path);
+
+    assert!(!output.status.success());
+    assert!(stderr(&output).starts_with(&format!{"error: {}:3:21: State Opened is a dead end", path.display()}));
+}
+
+#[test]
+fn renders_graphs() {
+    let path = file("graph.fsm", DOOR);
+
+    let dot = stdout(&run(&["graph"], &path));
+    assert!(dot.starts_with("digraph door {\n"));
+    assert!(dot.contains("    Broken [shape=doublecircle];\n"));
+    assert!(dot.contains("    Closed -> Opened [label=\"Open\"];\n"));
+
+    let mermaid = stdout(&run(&["graph", "--format", "mermaid"], &path));
+    assert!(mermaid.starts_with("---\ntitle: door\n---\nstateDiagram-v2\n    [*] --> Closed\n"));
+    assert!(mermaid.contains("    Closed --> Opened : Open\n"));
+    assert!(mermaid.ends_with("    Broken --> [*]\n"));
+
+    let plantuml = stdout(&run(&["graph", "--format", "plantuml"], &path));
+    assert!(plantuml.starts_with("@startuml door\n"));
+    assert!(plantuml.contains("    Opened --> Broken : Kick\n"));
+    assert!(plantuml.ends_with("@enduml\n"));
+
+    let output = run(&["graph", "--format", "svg"], &path);
+    assert!(!output.status.success());
+    assert!(stderr(&output).starts_with("error: unknown graph format `svg`, expected dot, mermaid or plantuml"));
+}
+
+#[test]
+fn counts_states_events_and_transitions() {
+    let path = file("stats.fsm", DOOR);
+    let output = run(&["stats"], &path);
+
+    assert_eq!(stdout(&output), format!{"{}: door
+    states:      3
+    initial:     1
+    final:       1
+    events:      3
+    transitions: 4
+", path.display()});
+}
+
+#[test]
+fn expands_machines() {
+    let path = file("expand.fsm", DOOR);
+    let output = run(&["expand"], &path);
+
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("impl Transition<Kick> for FSM<Opened> {"));
+}
+
+#[test]
+fn finds_machines_in_rust_sources() {
+    let path = file("machines.rs", &format!{"use static_fsm::fsm;\n\nfsm!({});\n\nfn main() {{\n    static_fsm::fsm!(other {{ Init[A] Final[B] Go: A => B }});\n}}\n", DOOR.trim()});
+    let output = run(&["check"], &path);
+
+    assert_eq!(stdout(&output), format!{"{0}: door: ok\n{0}: other: ok\n", path.display()});
+}
+
+#[test]
+fn reports_scxml_errors_without_positions() {
+    let path = file("door.scxml", r#"<scxml xmlns="http://www.w3.org/2005/07/scxml" version="1.0" initial="A"><state id="A"><transition event="Go" target="B"/></state><state id="B"/></scxml>"#);
+    let output = run(&["check"], &path);
+
+    assert!(!output.status.success());
+    assert_eq!(stderr(&output), format!{"error: {}: State B is a dead end: it has no outgoing transitions and is not declared Final, reached by: A --Go--> B\n  = help: allow with #[allow(dead_end_states)]\n", path.display()});
+}
+
+#[test]
+fn rejects_unknown_commands() {
+    let path = file("unknown.fsm", DOOR);
+    let output = run(&["render"], &path);
+
+    assert!(!output.status.success());
+    assert!(stderr(&output).starts_with("error: unknown command `render`"));
+}
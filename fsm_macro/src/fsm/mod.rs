@@ -1,7 +0,0 @@
-pub mod event;
-pub mod state;
-pub mod machine;
-pub mod transition;
-pub mod init;
-pub mod memory;
-pub mod trace;
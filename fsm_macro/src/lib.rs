@@ -1,14 +1,17 @@
-// Higher recursion limit for quote
-#![recursion_limit = "512"]
-
 extern crate proc_macro;
 
-use crate::fsm::machine::Machine;
+use fsm_model::Machine;
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::parse_macro_input;
+use syn::{
+    parse_macro_input,
+    LitStr,
+    Error
+};
 
-mod fsm;
+use std::path::PathBuf;
+use std::env;
+use std::fs;
 
 /// Generate the declaratively described state machine diagram.
 ///
@@ -16,9 +19,55 @@ mod fsm;
 #[proc_macro]
 pub fn fsm(input: TokenStream) -> TokenStream {
     let fsm: Machine = parse_macro_input!(input as Machine);
-    
-    let expanded = quote!(#fsm);
-//    println!("{}", expanded);
+
+    if let Err(err) = fsm.validate() {
+        return err.to_compile_error().into();
+    }
+
+    let warnings = fsm.warnings();
+    let expanded = quote! {
+        #fsm
+        #(#warnings)*
+    };
+
+    expanded.into()
+}
+
+/// Generate a state machine from a W3C SCXML document.
+///
+/// The path is resolved relative to the manifest directory of the invoking
+/// crate. Top level `<state>` and `<final>` elements become states, the
+/// `initial` attribute of `<scxml>` becomes the `Init` block and `<final>`
+/// elements the `Final` block. A document annotated with `fsm:init="false"`
+/// instead of `initial` becomes a machine without `Init` block, and an
+/// `fsm:after="N"` annotation on a `<transition>` a timed event. The machine
+/// is named after the `name` attribute of `<scxml>`, or the file stem if it
+/// has none. States are numbered in document order, events in the order of
+/// the `fsm:events` annotation of `<scxml>` or else of their first transition.
+#[proc_macro]
+pub fn fsm_scxml(input: TokenStream) -> TokenStream {
+    let lit: LitStr = parse_macro_input!(input as LitStr);
+
+    let root = env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".into());
+    let path = PathBuf::from(root).join(lit.value());
+
+    let fsm = fs::read_to_string(&path)
+        .map_err(|err| Error::new_spanned(&lit, format!{"Cannot read {}: {}", path.display(), err}))
+        .and_then(|text| Machine::from_scxml(&text, &path.display().to_string(), lit.span()))
+        .and_then(|fsm| fsm.validate().map(|_| fsm));
+
+    let fsm = match fsm {
+        Ok (fsm) => fsm,
+        Err (err) => return err.to_compile_error().into()
+    };
+
+    let warnings = fsm.warnings();
+    let path = LitStr::new(&path.to_string_lossy(), lit.span());
+    let expanded = quote! {
+        #fsm
+        #(#warnings)*
+        const _: &[u8] = include_bytes!(#path);
+    };
 
     expanded.into()
 }
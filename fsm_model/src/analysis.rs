@@ -0,0 +1,78 @@
+use proc_macro2::Ident;
+
+use std::collections::{
+    HashMap,
+    VecDeque
+};
+
+use crate::{
+    machine::Machine,
+    memory::StateMem,
+    transition::Transition
+};
+
+impl Machine {
+    /// Iterate over the transitions of every event.
+    pub fn transitions(&self) -> impl Iterator<Item = &Transition> {
+        self.events.iter().flat_map(|event| event.transitions.iter())
+    }
+
+    /// The states a machine can start in.
+    ///
+    /// These are the `Init` states or, if the machine has no `Init` block and
+    /// is only ever restored from storage, every state.
+    pub fn entries(&self) -> Vec<&Ident> {
+        if self.inits.iter().next().is_some() {
+            self.inits.iter().map(|init| &init.state).collect()
+        } else {
+            self.states.iter().map(|state| &state.state).collect()
+        }
+    }
+
+    /// Find a shortest path from an entry state to every reachable state.
+    ///
+    /// The path of an entry state is empty, states missing from the map are
+    /// unreachable. See [`Machine::entries`].
+    pub fn paths(&self) -> HashMap<&Ident, Vec<&Transition>> {
+        let mut paths: HashMap<&Ident, Vec<&Transition>> = HashMap::new();
+        let mut queue: VecDeque<&Ident> = VecDeque::new();
+
+        for entry in self.entries() {
+            if !paths.contains_key(entry) {
+                paths.insert(entry, Vec::new());
+                queue.push_back(entry);
+            }
+        }
+
+        while let Some(state) = queue.pop_front() {
+            for transition in self.transitions().filter(|transition| transition.prev == *state) {
+                if !paths.contains_key(&transition.next) {
+                    let mut path = paths[state].clone();
+                    path.push(transition);
+
+                    paths.insert(&transition.next, path);
+                    queue.push_back(&transition.next);
+                }
+            }
+        }
+
+        paths
+    }
+
+    /// The states that cannot be reached from any entry state.
+    pub fn unreachable(&self) -> Vec<&StateMem> {
+        let paths = self.paths();
+
+        self.states.iter()
+            .filter(|state| !paths.contains_key(&state.state))
+            .collect()
+    }
+
+    /// The states without outgoing transitions that are not declared `Final`.
+    pub fn dead_ends(&self) -> Vec<&StateMem> {
+        self.states.iter()
+            .filter(|state| !self.exits.iter().any(|exit| exit.state == state.state))
+            .filter(|state| !self.transitions().any(|transition| transition.prev == state.state))
+            .collect()
+    }
+}
@@ -0,0 +1,38 @@
+//! Code generation for the `proptest` and `quickcheck` features.
+//!
+//! `Events` implements `Arbitrary` by picking any event of the machine,
+//! whether the current state has a transition for it or not, which makes it
+//! suited for negative tests. Legal sequences are generated by the `Walk`
+//! of `static-fsm` instead.
+//!
+//! The implementations are wrapped in the `static_fsm::__private` macros,
+//! which drop each of them unless the matching feature of `static-fsm` is
+//! enabled.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// `Arbitrary` for the `Events` enum.
+pub(crate) fn events() -> TokenStream {
+    quote! {
+        static_fsm::__private::proptest! {
+            impl static_fsm::proptest::arbitrary::Arbitrary for Events {
+                type Parameters = ();
+                type Strategy = static_fsm::proptest::sample::Select<Events>;
+
+                fn arbitrary_with(_: ()) -> Self::Strategy {
+                    let all: &'static [Events] = &Events::ALL;
+                    static_fsm::proptest::sample::select(all)
+                }
+            }
+        }
+
+        static_fsm::__private::quickcheck! {
+            impl static_fsm::quickcheck::Arbitrary for Events {
+                fn arbitrary(g: &mut static_fsm::quickcheck::Gen) -> Self {
+                    *g.choose(&Events::ALL).expect("the machine has events")
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use syn::{
+    parse::{Parse, ParseStream, Result},
+    Attribute,
+    Ident,
+    LitInt,
+    Error
+};
+use proc_macro2::Span;
+use quote::ToTokens;
+
+use crate::suggest;
+
+/// The lints that can be disabled with `#[allow(...)]`.
+pub const LINTS: &[&str] = &[
+    "unreachable_states",
+    "dead_end_states",
+    "single_use_states",
+    "similar_events",
+];
+
+/// The integer types a `StateId` can be represented as.
+pub const REPRS: &[&str] = &[
+    "u8",
+    "u16",
+];
+
+/// The attributes written before the name of a machine.
+///
+/// ```text
+/// #[allow(unreachable_states)]
+/// #[repr(u8)]
+/// #[version(2)]
+/// door { ... }
+/// ```
+#[derive(Default)]
+pub struct Attrs {
+    /// The lints disabled with `#[allow(...)]`.
+    pub allow: Vec<Ident>,
+    /// The integer type of the `StateId` enum, set with `#[repr(...)]`.
+    pub repr: Option<Ident>,
+    /// The version written into snapshots, set with `#[version(...)]`.
+    pub version: Option<LitInt>
+}
+
+impl Attrs {
+    /// Whether the lint `lint` is disabled.
+    pub fn allows(&self, lint: &str) -> bool {
+        self.allow.iter().any(|allow| allow == lint)
+    }
+
+    /// The integer type of the `StateId` enum, `u16` unless set otherwise.
+    pub fn repr(&self) -> Ident {
+        match &self.repr {
+            Some (repr) => repr.clone(),
+            None => Ident::new("u16", Span::call_site())
+        }
+    }
+
+    /// The largest number of states the `StateId` enum can represent.
+    pub fn capacity(&self) -> usize {
+        match &self.repr {
+            Some (repr) if repr == "u8" => u8::MAX as usize + 1,
+            _ => u16::MAX as usize + 1
+        }
+    }
+}
+
+impl Parse for Attrs {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let mut attrs = Attrs::default();
+
+        for attr in input.call(Attribute::parse_outer)? {
+            if attr.path().is_ident("repr") {
+                let repr: Ident = attr.parse_args()?;
+
+                if !REPRS.iter().any(|known| repr == known) {
+                    let hint = suggest::hint(&repr.to_string(), REPRS.iter().copied());
+                    return Err(Error::new_spanned(&repr, format!{"Unsupported repr: {}{}\n  = help: expected one of: {}", repr, hint, REPRS.join(", ")}));
+                }
+
+                if let Some(first) = &attrs.repr {
+                    let mut err = Error::new_spanned(&repr, format!{"Duplicate repr: {}", repr});
+                    err.combine(Error::new_spanned(first, "First declared here"));
+
+                    return Err(err);
+                }
+
+                attrs.repr = Some (repr);
+                continue;
+            }
+
+            if attr.path().is_ident("version") {
+                let version: LitInt = attr.parse_args()?;
+                if version.base10_parse::<u16>()? == 0 {
+                    return Err(Error::new_spanned(&version, "Version 0 stands for the snapshots written before the machine declared a version\n  = help: start at #[version(1)]"));
+                }
+
+                if let Some(first) = &attrs.version {
+                    let mut err = Error::new_spanned(&version, format!{"Duplicate version: {}", version});
+                    err.combine(Error::new_spanned(first, "First declared here"));
+
+                    return Err(err);
+                }
+
+                attrs.version = Some (version);
+                continue;
+            }
+
+            if !attr.path().is_ident("allow") {
+                let hint = suggest::hint(&attr.path().to_token_stream().to_string(), ["allow", "repr", "version"]);
+                return Err(Error::new_spanned(attr.path(), format!{"Unknown machine attribute{}\n  = help: expected `#[allow(lint, ...)]`, `#[repr(u8|u16)]` or `#[version(N)]`", hint}));
+            }
+
+            attr.parse_nested_meta(|meta| {
+                let lint = meta.path.require_ident()?;
+
+                if !LINTS.iter().any(|known| lint == known) {
+                    let hint = suggest::hint(&lint.to_string(), LINTS.iter().copied());
+                    return Err(meta.error(format!{"Unknown lint: {}{}\n  = help: expected one of: {}", lint, hint, LINTS.join(", ")}));
+                }
+
+                attrs.allow.push(lint.clone());
+                Ok (())
+            })?;
+        }
+
+        Ok (attrs)
+    }
+}
@@ -0,0 +1,109 @@
+use proc_macro2::{
+    TokenStream,
+    Span
+};
+use quote::{quote_spanned, ToTokens};
+use syn::Error;
+
+use std::fmt::{
+    self,
+    Display
+};
+use std::path::Path;
+
+pub(crate) const INIT: &str = "Init[State, ...]";
+pub(crate) const MEMORY: &str = "Memory[State, ... (Type, ...), ...]";
+pub(crate) const TRACE: &str = "Trace[State, ...]";
+pub(crate) const FINAL: &str = "Final[State, ...]";
+pub(crate) const MIGRATE: &str = "Migrate[[#[repr(u8|u16)]] Version { State (Type, ...) => State, ... }, ...]";
+pub(crate) const EVENT: &str = "Event [after Ticks]: State, ... => State` or `Event [after Ticks] { State, ... => State, ... }";
+
+/// The names of the bracketed sections of a machine.
+pub(crate) const SECTIONS: &[&str] = &["Init", "Memory", "Trace", "Final", "Migrate"];
+
+/// Append the expected grammar to every message of `err`.
+pub(crate) fn help(err: Error, grammar: &str) -> Error {
+    err.into_iter()
+        .map(|err| Error::new(err.span(), format!{"{}\n  = help: expected `{}`", err, grammar}))
+        .reduce(|mut err, other| {
+            err.combine(other);
+            err
+        })
+        .expect("syn::Error holds at least one message")
+}
+
+/// The messages of an error about a machine read from a file.
+///
+/// Every message is written on its own line as `file:line:column: message`,
+/// or as `file: message` for spans without a position, e.g. those of
+/// machines imported from SCXML.
+pub struct Errors<'a> {
+    origin: &'a Path,
+    error: &'a Error,
+    positions: bool
+}
+
+impl<'a> Errors<'a> {
+    /// The messages of `error`, at the positions of their spans in `origin`.
+    pub fn new(origin: &'a Path, error: &'a Error) -> Self {
+        Self {
+            origin,
+            error,
+            positions: true
+        }
+    }
+
+    /// The messages of `error`, whose spans carry no position in `origin`.
+    pub fn without_positions(origin: &'a Path, error: &'a Error) -> Self {
+        Self {
+            origin,
+            error,
+            positions: false
+        }
+    }
+}
+
+impl Display for Errors<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, err) in self.error.clone().into_iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+
+            if self.positions {
+                let start = err.span().start();
+                write!(f, "{}:{}:{}: {}", self.origin.display(), start.line, start.column + 1, err)?;
+            } else {
+                write!(f, "{}: {}", self.origin.display(), err)?;
+            }
+        }
+
+        Ok (())
+    }
+}
+
+/// A diagnostic that does not prevent the machine from being generated.
+///
+/// Its [`ToTokens`] implementation emits the warning from a proc macro by
+/// using a deprecated item at `span`.
+pub struct Warning {
+    /// Where the warning points to.
+    pub span: Span,
+    /// The text of the warning.
+    pub message: String
+}
+
+impl ToTokens for Warning {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let message = &self.message;
+
+        tokens.extend(quote_spanned! {self.span=>
+            const _: () = {
+                #[deprecated(note = #message)]
+                #[allow(non_upper_case_globals)]
+                const fsm_warning: () = ();
+                fsm_warning
+            };
+        });
+    }
+}
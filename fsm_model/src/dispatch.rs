@@ -0,0 +1,266 @@
+//! Code generation for the runtime dispatch of events on `Variants`.
+//!
+//! The typestate API checks transitions at compile time, which requires the
+//! state to be known statically. Events received at runtime are dispatched
+//! on `Variants` instead: the `Events` enum names every event, and
+//! `Variants::dispatch` applies the typed transition of the current state,
+//! if there is one.
+//!
+//! Timed events, declared with `Event after Ticks: ...`, are listed per
+//! state by `StateId::timers` so that drivers can fire them.
+//!
+//! The memory of the current state is moved into the next state when both
+//! declare the same memory types. Otherwise the memory of the current state
+//! is dropped and the next state starts with `Default` memory; if one of its
+//! memory types has no `Default`, the transition is not available at
+//! runtime and the event is rejected like one without transition.
+//! `StateId::next`, and with it the `Table` of the machine, leaves such
+//! transitions out as well, so that the runtime tools agree with `dispatch`.
+
+use proc_macro2::{
+    Literal,
+    TokenStream
+};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    Ident,
+    Type
+};
+
+use crate::machine::Machine;
+
+/// The `Events` enum, the names of states and events and `Variants::dispatch`.
+pub(crate) fn tokens(machine: &Machine) -> TokenStream {
+    let events: Vec<&Ident> = machine.events.iter().map(|event| &event.name).collect();
+    let event_names: Vec<String> = events.iter().map(|event| event.to_string()).collect();
+    let event_count = events.len();
+    let machine_name = machine.name.to_string();
+    let inits: Vec<&Ident> = machine.inits.iter().map(|init| &init.state).collect();
+
+    let states: Vec<&Ident> = machine.states.iter().map(|state| &state.state).collect();
+    let state_names: Vec<String> = states.iter().map(|state| state.to_string()).collect();
+
+    let memory = |state: &Ident| -> &[Type] {
+        machine.states.iter()
+            .find(|current| current.state == *state)
+            .and_then(|current| current.memory.as_deref())
+            .unwrap_or_default()
+    };
+
+    let mut next: Vec<TokenStream> = Vec::new();
+    let mut dispatch: Vec<TokenStream> = Vec::new();
+
+    for event in machine.events.iter() {
+        let name = &event.name;
+
+        for transition in event.transitions.iter() {
+            let prev = &transition.prev;
+            let target = &transition.next;
+
+            let old = memory(prev);
+            let new = memory(target);
+
+            if same(old, new) || new.is_empty() {
+                next.push(quote!((StateId::#prev, Events::#name) => Some (StateId::#target)));
+            } else {
+                next.push(quote!((StateId::#prev, Events::#name) if #((&Memory::<#new>::new()).has_default())&&* => Some (StateId::#target)));
+            }
+
+            let names: Vec<Ident> = (0..old.len()).map(|i| format_ident!("in_{}", i)).collect();
+
+            if same(old, new) {
+                dispatch.push(quote! {
+                    (Variants::#prev(fsm, #(#names),*), Events::#name) => {
+                        Ok (Variants::#target(Transition::<#name>::t(fsm, #name), #(#names),*))
+                    }
+                });
+            } else {
+                let values: Vec<Ident> = (0..new.len()).map(|i| format_ident!("out_{}", i)).collect();
+
+                dispatch.push(quote! {
+                    (Variants::#prev(fsm, #(#names),*), Events::#name) => {
+                        #[allow(unused_imports)]
+                        use static_fsm::__private::{Memory, DefaultMemory as _, NoDefaultMemory as _};
+
+                        #[allow(unused_variables)]
+                        match (#((&Memory::<#new>::new()).memory(),)*) {
+                            (#(Some (#values),)*) => Ok (Variants::#target(Transition::<#name>::t(fsm, #name), #(#values),*)),
+                            #[allow(unreachable_patterns)]
+                            _ => Err(Variants::#prev(fsm, #(#names),*))
+                        }
+                    }
+                });
+            }
+        }
+    }
+
+    let numbers: Vec<Literal> = (0..event_count).map(Literal::usize_unsuffixed).collect();
+
+    let delays: Vec<TokenStream> = machine.events.iter()
+        .map(|event| match event.delay() {
+            Some (delay) => quote!(Some (#delay)),
+            None => quote!(None)
+        })
+        .collect();
+
+    let timers: Vec<TokenStream> = states.iter()
+        .map(|state| {
+            let timers = machine.events.iter()
+                .filter(|event| event.transitions.iter().any(|transition| transition.prev == **state))
+                .filter_map(|event| event.delay().map(|delay| (&event.name, delay)))
+                .map(|(event, delay)| quote!((Events::#event, #delay)));
+
+            quote!(StateId::#state => &[#(#timers),*])
+        })
+        .collect();
+
+    let arbitrary = crate::arbitrary::events();
+
+    quote! {
+        /// The events of the machine as plain values, in declaration order.
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+        pub enum Events {
+            #(#events = #numbers,)*
+        }
+
+        impl Events {
+            /// The number of events.
+            pub const COUNT: usize = #event_count;
+
+            /// Every event, in declaration order.
+            pub const ALL: [Events; #event_count] = [#(Events::#events),*];
+
+            /// The name of the event.
+            pub const fn name(self) -> &'static str {
+                match self {
+                    #(Events::#events => #event_names,)*
+                }
+            }
+
+            /// The delay in clock ticks after which the event fires by
+            /// itself, if it is timed.
+            pub const fn after(self) -> Option<u64> {
+                match self {
+                    #(Events::#events => #delays,)*
+                }
+            }
+        }
+
+        #arbitrary
+
+        #(
+            impl From<#events> for Events {
+                fn from(_: #events) -> Self {
+                    Events::#events
+                }
+            }
+        )*
+
+        impl StateId {
+            /// The name of the state.
+            pub const fn name(self) -> &'static str {
+                match self {
+                    #(StateId::#states => #state_names,)*
+                }
+            }
+
+            /// The timed events leaving this state and their delays in clock
+            /// ticks, in declaration order. Drivers only fire those that
+            /// `next` has a transition for.
+            pub const fn timers(self) -> &'static [(Events, u64)] {
+                match self {
+                    #(#timers,)*
+                }
+            }
+
+            /// The state `event` leads to from this state at runtime, if any.
+            ///
+            /// Leaves out the transitions `Variants::dispatch` rejects because
+            /// the memory of the next state has no `Default`.
+            pub fn next(self, event: Events) -> Option<StateId> {
+                #[allow(unused_imports)]
+                use static_fsm::__private::{Memory, DefaultMemory as _, NoDefaultMemory as _};
+
+                match (self, event) {
+                    #(#next,)*
+                    #[allow(unreachable_patterns)]
+                    _ => None
+                }
+            }
+        }
+
+        impl static_fsm::Table for StateId {
+            type Event = Events;
+
+            const STATES: &'static [StateId] = &StateId::ALL;
+            const EVENTS: &'static [Events] = &Events::ALL;
+            const INITS: &'static [StateId] = &[#(StateId::#inits),*];
+
+            fn next(self, event: Events) -> Option<StateId> {
+                StateId::next(self, event)
+            }
+
+            fn name(self) -> &'static str {
+                StateId::name(self)
+            }
+
+            fn event_name(event: Events) -> &'static str {
+                event.name()
+            }
+        }
+
+        impl Variants {
+            /// Apply `event` to the machine.
+            ///
+            /// Gives the machine back unchanged as the error if the current
+            /// state has no transition for `event`, or if the transition
+            /// enters a state whose memory differs from the current one and
+            /// has no `Default`.
+            pub fn dispatch(self, event: Events) -> Result<Variants, Variants> {
+                match (self, event) {
+                    #(#dispatch,)*
+                    #[allow(unreachable_patterns)]
+                    (variant, _) => Err(variant)
+                }
+            }
+        }
+
+        impl static_fsm::Dispatch for Variants {
+            type Id = StateId;
+            type Event = Events;
+
+            const NAME: &'static str = #machine_name;
+
+            fn id(&self) -> StateId {
+                Variants::id(self)
+            }
+
+            fn dispatch(self, event: Events) -> Result<Variants, Variants> {
+                Variants::dispatch(self, event)
+            }
+
+            fn next(id: StateId, event: Events) -> Option<StateId> {
+                id.next(event)
+            }
+
+            fn timers(id: StateId) -> &'static [(Events, u64)] {
+                id.timers()
+            }
+
+            fn state_name(id: StateId) -> &'static str {
+                id.name()
+            }
+
+            fn event_name(event: Events) -> &'static str {
+                event.name()
+            }
+        }
+    }
+}
+
+/// Whether two memory declarations have the same types.
+fn same(old: &[Type], new: &[Type]) -> bool {
+    old.len() == new.len() && old.iter().zip(new).all(|(old, new)| {
+        old.to_token_stream().to_string() == new.to_token_stream().to_string()
+    })
+}
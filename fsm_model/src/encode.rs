@@ -0,0 +1,258 @@
+//! Code generation for the binary snapshots of `Variants`.
+//!
+//! The layout is documented with the `Encode` trait of `static-fsm`: the
+//! version of the machine, if declared with `#[version(...)]`, then the
+//! state ID followed by the memory of the state. The implementations are
+//! bounded on the memory types implementing `Encode`, so machines whose
+//! memory cannot be encoded still compile as long as no snapshot is taken.
+//!
+//! Snapshots of the versions in the `Migrate` blocks are decoded with the
+//! states, memory layout and repr of that version. Each memory value is
+//! converted with `Into` into the value at the same position in the memory
+//! of the mapped state, missing values are `Default`ed and extra values
+//! dropped. Unlike the current memory, the conversions are not bounds: they
+//! are checked where the `Migrate` block is written. Version 0 stands for
+//! the snapshots written before the machine declared a version, which have
+//! no version header.
+
+use proc_macro2::{
+    Literal,
+    TokenStream
+};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{
+    spanned::Spanned,
+    Ident,
+    Type
+};
+
+use crate::{
+    machine::Machine,
+    migrate::Migration
+};
+
+/// `Encode` and the inherent `encode`/`decode` methods for `Variants`.
+pub(crate) fn variants(machine: &Machine) -> TokenStream {
+    let repr = machine.attrs.repr();
+
+    let bounds: Vec<TokenStream> = machine.variants.iter()
+        .flat_map(|variant| variant.memory.iter().flatten())
+        .map(|ty| quote!(for<'a> #ty: static_fsm::Encode))
+        .collect();
+
+    let mut encode: Vec<TokenStream> = Vec::new();
+    let mut decode: Vec<TokenStream> = Vec::new();
+
+    for variant in machine.variants.iter() {
+        let state = &variant.state;
+
+        match &variant.memory {
+            Some (memory) => {
+                let names: Vec<Ident> = (0..memory.len()).map(|i| format_ident!("in_{}", i)).collect();
+
+                encode.push(quote! {
+                    Variants::#state(_, #(#names),*) => {
+                        #(len += static_fsm::Encode::encode(#names, &mut buf[len..])?;)*
+                    }
+                });
+                decode.push(quote! {
+                    Some (StateId::#state) => {
+                        #(
+                            let (#names, read) = static_fsm::Encode::decode(&buf[len..])?;
+                            len += read;
+                        )*
+                        Variants::#state(FSM { _s: PhantomData }, #(#names),*)
+                    }
+                });
+            },
+            None => {
+                encode.push(quote!(Variants::#state(_) => {}));
+                decode.push(quote!(Some (StateId::#state) => Variants::#state(FSM { _s: PhantomData })));
+            }
+        }
+    }
+
+    let migrations: Vec<TokenStream> = machine.migrations.iter()
+        .map(|migration| migrate(machine, migration))
+        .collect();
+
+    let (encode_version, decode_version, decode_unversioned) = match &machine.attrs.version {
+        Some (version) => {
+            let version = Literal::u16_unsuffixed(version.base10_parse().unwrap_or_default());
+            let older: Vec<&Migration> = machine.migrations.iter()
+                .filter(|migration| migration.number() != 0)
+                .collect();
+            let numbers: Vec<Literal> = older.iter()
+                .map(|migration| Literal::u16_unsuffixed(migration.number()))
+                .collect();
+            let functions: Vec<Ident> = older.iter()
+                .map(|migration| format_ident!("migrate_{}", migration.number()))
+                .collect();
+            let unversioned = if machine.migrations.iter().any(|migration| migration.number() == 0) {
+                quote!(Variants::migrate_0(buf, 0))
+            } else {
+                quote!(Variants::decode_layout(buf, 0))
+            };
+
+            (
+                quote! {
+                    len += static_fsm::Encode::encode(&(#version as u16), buf)?;
+                },
+                quote! {
+                    let (version, read) = <u16 as static_fsm::Encode>::decode(buf)?;
+                    len += read;
+
+                    match version {
+                        #version => {},
+                        #(#numbers => return Variants::#functions(buf, len),)*
+                        _ => return Err(static_fsm::EncodeError::UnknownVersion(version))
+                    }
+                },
+                Some (quote! {
+                    /// Restore a machine from a snapshot written before it
+                    /// declared `#[version(...)]`, which has no version header.
+                    ///
+                    /// Such snapshots are version 0: they are decoded with the
+                    /// `Migrate` block of version 0, or with the current layout
+                    /// if there is none.
+                    pub fn decode_unversioned(buf: &[u8]) -> Result<Self, static_fsm::EncodeError>
+                    where
+                        #(#bounds,)*
+                    {
+                        #unversioned.map(|(variant, _)| variant)
+                    }
+                })
+            )
+        },
+        None => (TokenStream::new(), TokenStream::new(), None)
+    };
+
+    quote! {
+        impl static_fsm::Encode for Variants
+        where
+            #(#bounds,)*
+        {
+            fn encode(&self, buf: &mut [u8]) -> Result<usize, static_fsm::EncodeError> {
+                let mut len = 0;
+                #encode_version
+                len += static_fsm::Encode::encode(&self.discriminant(), &mut buf[len..])?;
+
+                match self {
+                    #(#encode),*
+                }
+
+                Ok (len)
+            }
+
+            fn decode(buf: &[u8]) -> Result<(Self, usize), static_fsm::EncodeError> {
+                #[allow(unused_mut)]
+                let mut len = 0;
+                #decode_version
+                Variants::decode_layout(buf, len)
+            }
+        }
+
+        impl Variants {
+            /// Write a snapshot of the machine to the start of `buf`.
+            ///
+            /// Returns the number of bytes written.
+            pub fn encode(&self, buf: &mut [u8]) -> Result<usize, static_fsm::EncodeError>
+            where
+                for<'a> Self: static_fsm::Encode
+            {
+                static_fsm::Encode::encode(self, buf)
+            }
+
+            /// Restore a machine from a snapshot written by `encode`.
+            ///
+            /// Snapshots of the versions in the `Migrate` blocks are migrated
+            /// to the current states.
+            pub fn decode(buf: &[u8]) -> Result<Self, static_fsm::EncodeError>
+            where
+                for<'a> Self: static_fsm::Encode
+            {
+                <Self as static_fsm::Encode>::decode(buf).map(|(variant, _)| variant)
+            }
+
+            #decode_unversioned
+
+            /// Decode the state ID and memory at `len` with the current layout.
+            #[allow(unused_variables)]
+            fn decode_layout(buf: &[u8], mut len: usize) -> Result<(Self, usize), static_fsm::EncodeError>
+            where
+                #(#bounds,)*
+            {
+                let (id, read) = <#repr as static_fsm::Encode>::decode(&buf[len..])?;
+                len += read;
+
+                let variant = match StateId::from_u16(id as u16) {
+                    #(#decode,)*
+                    None => return Err(static_fsm::EncodeError::UnknownState(id as u16))
+                };
+
+                Ok ((variant, len))
+            }
+
+            #(#migrations)*
+        }
+    }
+}
+
+/// The function decoding the state ID and memory of a snapshot of the version
+/// of `migration`, starting at `len`.
+///
+/// The conversions are spanned at the types of the `Migrate` block, where a
+/// missing `Into` or `Default` is reported.
+fn migrate(machine: &Machine, migration: &Migration) -> TokenStream {
+    let function = format_ident!("migrate_{}", migration.number());
+    let repr = migration.repr();
+
+    let arms: Vec<TokenStream> = migration.states.iter().enumerate().map(|(id, state)| {
+        let id = Literal::usize_unsuffixed(id);
+        let target = &state.target;
+
+        let old: &[Type] = state.memory.as_deref().unwrap_or_default();
+        let new: &[Type] = machine.states.iter()
+            .find(|current| current.state == *target)
+            .and_then(|current| current.memory.as_deref())
+            .unwrap_or_default();
+
+        let names: Vec<Ident> = (0..old.len()).map(|i| format_ident!("in_{}", i)).collect();
+        let values: Vec<TokenStream> = new.iter().enumerate().map(|(i, ty)| match old.get(i) {
+            Some (old) => {
+                let name = &names[i];
+                quote_spanned!(old.span()=> <#old as Into<#ty>>::into(#name))
+            },
+            None => quote_spanned!(target.span()=> <#ty as Default>::default())
+        }).collect();
+        let decode: Vec<TokenStream> = old.iter()
+            .map(|ty| quote_spanned!(ty.span()=> <#ty as static_fsm::Encode>::decode(&buf[len..])))
+            .collect();
+
+        quote! {
+            #id => {
+                #(
+                    let (#names, read) = #decode?;
+                    len += read;
+                )*
+                Variants::#target(FSM { _s: PhantomData }, #(#values),*)
+            }
+        }
+    }).collect();
+
+    quote! {
+        /// Decode the state ID and memory at `len` with the layout of an
+        /// older version.
+        fn #function(buf: &[u8], mut len: usize) -> Result<(Self, usize), static_fsm::EncodeError> {
+            let (id, read) = <#repr as static_fsm::Encode>::decode(&buf[len..])?;
+            len += read;
+
+            let variant = match id {
+                #(#arms,)*
+                _ => return Err(static_fsm::EncodeError::UnknownState(id as u16))
+            };
+
+            Ok ((variant, len))
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{
+    braced,
+    parse::{Parse, ParseStream, Result},
+    token::{
+        Comma,
+        Colon,
+        Brace,
+        Bracket
+    },
+    Token,
+    Ident,
+    LitInt,
+    Error
+};
+
+use std::hash::{
+    Hasher,
+    Hash
+};
+
+use std::slice::Iter;
+
+use crate::transition::{
+    Transitions,
+    Transition
+};
+use crate::{
+    state::State,
+    diagnostic::{self, EVENT, SECTIONS},
+    suggest
+};
+
+pub(crate) struct EDefinition {
+    pub states: Vec<State>,
+    pub event: Event
+}
+
+/// The events of a machine.
+pub struct Events {
+    events: Vec<Event>
+}
+
+/// An event and the transitions it triggers.
+pub struct Event {
+    /// The name of the event struct.
+    pub name: Ident,
+    /// The transitions triggered by the event.
+    pub transitions: Transitions,
+    /// The states named in the definition, as written: every origin and the
+    /// destination of each clause.
+    pub mentions: Vec<Ident>,
+    /// The delay in clock ticks after which the event fires by itself, set
+    /// with `Event after Ticks: ...`.
+    pub after: Option<LitInt>
+}
+
+impl Event {
+    /// The delay in clock ticks, if the event is timed.
+    pub fn delay(&self) -> Option<u64> {
+        self.after.as_ref().map(|after| after.base10_parse().unwrap_or_default())
+    }
+}
+
+impl Parse for EDefinition {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let mut orig_states: Vec<State> = Vec::new();
+        let mut states: Vec<State> = Vec::new();
+        let mut transitions: Vec<Transition> = Vec::new();
+        let mut mentions: Vec<Ident> = Vec::new();
+
+        let event: Ident = input.parse().map_err(|err| diagnostic::help(err, EVENT))?;
+
+        if input.peek(Bracket) {
+            let hint = suggest::hint(&event.to_string(), SECTIONS.iter().copied());
+            return Err(Error::new_spanned(&event, format!{"Unknown section: {}{}", event, hint}));
+        }
+
+        let fork = input.fork();
+        let after = match fork.parse::<Ident>() {
+            Ok (after) if after == "after" => {
+                let _: Ident = input.parse()?;
+                let ticks: LitInt = input.parse().map_err(|err| diagnostic::help(err, EVENT))?;
+                ticks.base10_parse::<u64>()?;
+
+                Some (ticks)
+            },
+            _ => None
+        };
+
+        if !input.peek(Token![:]) && !input.peek(Brace) {
+            return Err(diagnostic::help(input.error(format!{"Expected `:` or `{{` after event {}", event}), EVENT));
+        }
+
+        if input.peek(Token![:]) {
+            let _: Colon = input.parse().map_err(|err| diagnostic::help(err, EVENT))?;
+            let mut def_states: Vec<Ident> = Vec::new();
+
+            loop {
+                let state: State = input.parse().map_err(|err| diagnostic::help(err, EVENT))?;
+                if let Some(first) = orig_states.iter().find(|first| **first == state) {
+                    let mut err = Error::new_spanned(&state.name, format!{"Duplicate transition origin: {}", state.name});
+                    err.combine(Error::new_spanned(&first.name, "First declared here"));
+
+                    return Err(err);
+                }
+
+                orig_states.push(state.clone());
+                declare(&mut states, &state);
+                mentions.push(state.name.clone());
+                def_states.push(state.name);
+
+                if input.peek(Token![,]) {
+                    let _: Comma = input.parse().map_err(|err| diagnostic::help(err, EVENT))?;
+                } else {
+                    break;
+                }
+            }
+
+            let _: Token![=>] = input.parse().map_err(|err| diagnostic::help(err, EVENT))?;
+
+            let dest_state: State = input.parse().map_err(|err| diagnostic::help(err, EVENT))?;
+            declare(&mut states, &dest_state);
+            mentions.push(dest_state.name.clone());
+
+            transitions.extend(Transitions::generate(def_states, dest_state.name, event.clone()));
+
+            
+        } else {
+
+            let event_blk;
+            braced!(event_blk in input);
+
+            while !event_blk.is_empty() {
+                let mut def_states: Vec<Ident> = Vec::new();
+
+                loop {
+                    let state: State = event_blk.parse().map_err(|err| diagnostic::help(err, EVENT))?;
+                    if let Some(first) = orig_states.iter().find(|first| **first == state) {
+                        let mut err = Error::new_spanned(&state.name, format!{"Duplicate transition origin: {}", state.name});
+                        err.combine(Error::new_spanned(&first.name, "First declared here"));
+
+                        return Err(err);
+                    }
+
+                    orig_states.push(state.clone());
+                    declare(&mut states, &state);
+                    mentions.push(state.name.clone());
+                    def_states.push(state.name);
+
+                    if event_blk.peek(Token![,]) {
+                        let _: Comma = event_blk.parse().map_err(|err| diagnostic::help(err, EVENT))?;
+                    } else {
+                        break;
+                    }
+                }
+
+                let _: Token![=>] = event_blk.parse().map_err(|err| diagnostic::help(err, EVENT))?;
+
+                let dest_state: State = event_blk.parse().map_err(|err| diagnostic::help(err, EVENT))?;
+                declare(&mut states, &dest_state);
+                mentions.push(dest_state.name.clone());
+
+                transitions.extend(Transitions::generate(def_states, dest_state.name, event.clone()));
+                
+                if event_blk.is_empty() {
+                    break;
+                }
+
+                let _: Comma = event_blk.parse().map_err(|err| diagnostic::help(err, EVENT))?;
+            }
+
+        }
+
+        Ok ( EDefinition {
+            states,
+            event: Event { 
+                name: event, 
+                transitions: transitions.into(),
+                mentions,
+                after
+            }
+        } )
+    }
+}
+
+/// Add `state` to `states` unless it is already there.
+fn declare(states: &mut Vec<State>, state: &State) {
+    if !states.contains(state) {
+        states.push(state.clone());
+    }
+}
+
+impl From<Events> for Vec<Event> {
+    fn from(events: Events) -> Self {
+        events.events
+    }
+}
+
+impl From<Vec<Event>> for Events {
+    fn from(events: Vec<Event>) -> Self {
+        Self {
+            events
+        }
+    }
+}
+
+impl Events {
+    /// Iterate over the events.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, Event> {
+        self.events.iter()
+    }
+}
+
+impl ToTokens for Events {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.iter().for_each(|event| event.to_tokens(tokens));
+    }
+}
+
+impl ToTokens for Event {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let name = &self.name;
+        let transitions = &self.transitions;
+        let serde = crate::serde::unit(name);
+
+        tokens.extend(quote! {
+            #[derive(Clone, Copy, PartialEq, Eq)]
+            pub struct #name;
+            impl Event for #name {}
+            #serde
+
+            #transitions
+        });
+    }
+}
+
+impl PartialEq for Event {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for Event { }
+
+impl Hash for Event {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state)
+    }
+}
+
+
+
+
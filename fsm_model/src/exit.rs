@@ -0,0 +1,156 @@
+use proc_macro2::TokenStream;
+
+use quote::{quote, ToTokens};
+use syn::{
+    bracketed,
+    parse::{Parse, ParseStream, Result},
+    token::{
+        Comma,
+        Bracket
+    },
+    Ident,
+    Error
+};
+
+use std::hash::{
+    Hasher,
+    Hash
+};
+
+use std::slice::Iter;
+
+use crate::{
+    state::State,
+    diagnostic::{self, FINAL}
+};
+
+/// The states declared in the `Final` block.
+pub struct Exits {
+    exits: Vec<Exit>
+}
+
+impl Exits {
+    /// Iterate over the final states.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, Exit> {
+        self.exits.iter()
+    }
+}
+
+impl From<Exits> for Vec<Exit> {
+    fn from(exits: Exits) -> Self {
+        exits.exits
+    }
+}
+
+impl From<Vec<Exit>> for Exits {
+    fn from(exits: Vec<Exit>) -> Self {
+        Self {
+            exits
+        }
+    }
+}
+
+impl IntoIterator for Exits {
+    type Item = Exit;
+    type IntoIter = std::vec::IntoIter<Exit>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.exits.into_iter()
+    }
+}
+
+/// A state declared in the `Final` block.
+#[derive(Clone)]
+pub struct Exit {
+    /// The final state.
+    pub state: Ident
+}
+
+impl Parse for Exits {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let mut exits: Vec<Exit> = Vec::new();
+
+        let fork = input.fork();
+        if let Ok (exit) = fork.parse::<Ident>() {
+            if exit == "Final" {
+                let _: Ident = input.parse()?;
+
+                let exit_blk;
+                if !input.peek(Bracket) {
+                    return Err(diagnostic::help(input.error("Expected `[`"), FINAL));
+                }
+                bracketed!(exit_blk in input);
+
+                while !exit_blk.is_empty() {
+                    let exit_state: Exit = exit_blk.parse().map_err(|err| diagnostic::help(err, FINAL))?;
+
+                    if let Some(first_exit) = exits.iter().find(|first| **first == exit_state) {
+                        let mut err = Error::new_spanned(&exit_state.state, format!{"Duplicate final state: {}", exit_state.state});
+                        err.combine(Error::new_spanned(&first_exit.state, "First declared here"));
+
+                        return Err(err);
+                    }
+
+                    exits.push(exit_state);
+
+                    if exit_blk.is_empty() {
+                        break;
+                    }
+
+                    let _: Comma = exit_blk.parse().map_err(|err| diagnostic::help(err, FINAL))?;
+                }
+            }
+        }
+
+        Ok ( Exits {
+            exits
+        } )
+    }
+}
+
+impl From<Exit> for State {
+    fn from(exit: Exit) -> Self {
+        Self {
+            name: exit.state
+        }
+    }
+}
+
+impl Parse for Exit {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        Ok ( Exit {
+            state: input.parse()?
+        })
+    }
+}
+
+impl ToTokens for Exits {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.iter().for_each(|exit| exit.to_tokens(tokens));
+    }
+}
+
+impl ToTokens for Exit {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let state = &self.state;
+
+        tokens.extend(quote! {
+            impl static_fsm::ExitPoint for #state { }
+        });
+    }
+}
+
+impl PartialEq for Exit {
+    fn eq(&self, other: &Self) -> bool {
+        self.state == other.state
+    }
+}
+
+impl Eq for Exit { }
+
+impl Hash for Exit {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.state.hash(state)
+    }
+}
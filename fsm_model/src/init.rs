@@ -4,7 +4,10 @@ use quote::{quote, ToTokens};
 use syn::{
     bracketed,
     parse::{Parse, ParseStream, Result},
-    token::Comma,
+    token::{
+        Comma,
+        Bracket
+    },
     Ident,
     Error
 };
@@ -14,32 +17,34 @@ use std::hash::{
     Hash
 };
 
-use std::collections::{
-    hash_set::Iter,
-    HashSet
-};
+use std::slice::Iter;
 
-use crate::fsm::state::State;
+use crate::{
+    state::State,
+    diagnostic::{self, INIT}
+};
 
-pub(crate) struct Inits {
-    inits: HashSet<Init>
+/// The states declared in the `Init` block.
+pub struct Inits {
+    inits: Vec<Init>
 }
 
 impl Inits {
+    /// Iterate over the initial states.
     #[inline]
-    pub(crate) fn iter(&self) -> Iter<'_, Init> {
+    pub fn iter(&self) -> Iter<'_, Init> {
         self.inits.iter()
     }
 }
 
-impl Into<HashSet<Init>> for Inits {
-    fn into(self) -> HashSet<Init> {
-        self.inits
+impl From<Inits> for Vec<Init> {
+    fn from(inits: Inits) -> Self {
+        inits.inits
     }
 }
 
-impl From<HashSet<Init>> for Inits {
-    fn from(inits: HashSet<Init>) -> Self {
+impl From<Vec<Init>> for Inits {
+    fn from(inits: Vec<Init>) -> Self {
         Self {
             inits
         }
@@ -48,52 +53,53 @@ impl From<HashSet<Init>> for Inits {
 
 impl IntoIterator for Inits {
     type Item = Init;
-    type IntoIter = std::collections::hash_set::IntoIter<Init>;
+    type IntoIter = std::vec::IntoIter<Init>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.inits.into_iter()
     }
 }
 
+/// A state declared in the `Init` block.
 #[derive(Clone)]
-pub(crate) struct Init {
+pub struct Init {
+    /// The initial state.
     pub state: Ident
 }
 
 impl Parse for Inits {
     fn parse(input: ParseStream<'_>) -> Result<Self> {
-        let mut inits: HashSet<Init> = HashSet::new();
+        let mut inits: Vec<Init> = Vec::new();
 
-        let init: Ident = input.parse()?;
-        if init != "Init" {
-            let err = Error::new_spanned(&init, format!{"Expected Init block, got: {}", init});
-            return Err(err);
-        }
+        let _: Ident = input.parse().map_err(|err| diagnostic::help(err, INIT))?;
 
         let init_blk;
+        if !input.peek(Bracket) {
+            return Err(diagnostic::help(input.error("Expected `[`"), INIT));
+        }
         bracketed!(init_blk in input);
 
         while !init_blk.is_empty() {
-            let init_state: Init = init_blk.parse()?;
+            let init_state: Init = init_blk.parse().map_err(|err| diagnostic::help(err, INIT))?;
             
-            if let Some(first_init) = inits.get(&init_state) {
-                let mut err = Error::new_spanned(&init_state.state, format!{"Duplicate sate initialisation: {}", init_state.state});
-                err.combine(Error::new_spanned(&first_init.state, format!{"First declared here"}));
+            if let Some(first_init) = inits.iter().find(|first| **first == init_state) {
+                let mut err = Error::new_spanned(&init_state.state, format!{"Duplicate initial state: {}", init_state.state});
+                err.combine(Error::new_spanned(&first_init.state, "First declared here"));
 
                 return Err(err);
             }
 
-            inits.insert(init_state);
+            inits.push(init_state);
 
             if init_blk.is_empty() {
                 break;
             }
 
-            let _: Comma = init_blk.parse()?;
+            let _: Comma = init_blk.parse().map_err(|err| diagnostic::help(err, INIT))?;
         }
 
         Ok ( Inits {
-            inits: inits.into()
+            inits
         } )
     }
 }
@@ -0,0 +1,75 @@
+//! Parser, intermediate representation and code generator of `static-fsm`.
+//!
+//! A machine definition has the grammar accepted by the `fsm!` macro:
+//!
+//! ```text
+//! #[allow(unreachable_states)]
+//! #[repr(u8)]
+//! door {
+//!     Init[Closed]
+//!     Memory[Opened (u32)]
+//!     Trace[Closed]
+//!     Final[Broken]
+//!     Open: Closed => Opened
+//!     Close { Opened => Closed }
+//!     Kick { Closed, Opened => Broken }
+//!     AutoClose after 500: Opened => Closed
+//! }
+//! ```
+//!
+//! The sections may appear in any order and more than once. Without an `Init`
+//! section the machine has no entry point and is only ever restored from
+//! storage. `#[repr(u8)]` or
+//! `#[repr(u16)]`, the default, sets the integer type of the generated
+//! `StateId` enum. `#[version(N)]` writes the version into snapshots, and
+//! `Migrate[1 { Idle => Closed, Opened (u16) }]` restores the snapshots of
+//! version 1, whose states were `Idle` and `Opened` with a `u16` memory.
+//!
+//! [`Machine::parse_str`] turns such a definition into a [`Machine`],
+//! [`Machine::validate`] checks it, the analyses on [`Machine`] answer
+//! questions such as which states are reachable, and the [`quote::ToTokens`]
+//! implementation of [`Machine`] generates the same typestate module as
+//! `fsm!`. Build scripts, command line tools and tests can use this crate
+//! to inspect or generate machines without going through the proc macro.
+//!
+//! The IR is built from `syn` and `proc_macro2` types, e.g. names are
+//! [`syn::Ident`]s, memory types [`syn::Type`]s and versions
+//! [`syn::LitInt`]s. Its public API therefore follows the semver of `syn` 2
+//! and `proc-macro2` 1: a new major version of either is a new major
+//! version of this crate.
+
+// Higher recursion limit for quote
+#![recursion_limit = "512"]
+
+mod event;
+mod state;
+mod machine;
+mod transition;
+mod init;
+mod exit;
+mod memory;
+mod trace;
+mod migrate;
+mod scxml;
+mod attr;
+mod analysis;
+mod diagnostic;
+mod suggest;
+mod serde;
+mod encode;
+mod dispatch;
+mod arbitrary;
+
+pub use crate::{
+    machine::Machine,
+    state::{States, State},
+    event::{Events, Event},
+    transition::{Transitions, Transition},
+    init::{Inits, Init},
+    exit::{Exits, Exit},
+    memory::{MemDefs, MemDef, StateMems, StateMem},
+    trace::Traces,
+    migrate::{Migrations, Migration, Migrated},
+    attr::{Attrs, LINTS, REPRS},
+    diagnostic::{Errors, Warning}
+};
@@ -0,0 +1,556 @@
+
+use proc_macro2::{
+    Literal,
+    TokenStream
+};
+use quote::{quote, ToTokens};
+use syn::{
+    braced,
+    parse::{Parse, ParseStream, Result},
+    token::Bracket,
+    Ident,
+    Error
+};
+
+use crate::{
+    event::{
+        EDefinition,
+        Events,
+        Event
+    },
+    state::State,
+    init::{
+        Inits,
+        Init
+    },
+    exit::{
+        Exits,
+        Exit
+    },
+    memory::{
+        MemDefBlk,
+        MemDef,
+        MemDefs,
+        StateMems,
+        StateMem
+    },
+    trace::Traces,
+    migrate::{
+        Migrations,
+        Migration
+    },
+    attr::Attrs,
+    diagnostic::Warning,
+    suggest
+};
+
+/// A parsed state machine definition.
+///
+/// This is the root of the IR. It is produced by parsing the body of an
+/// `fsm!` invocation (see [`Machine::parse_str`]) or an SCXML document (see
+/// [`Machine::from_scxml`]), and expands to the typestate module generated
+/// by `fsm!` through its [`ToTokens`] implementation.
+pub struct Machine {
+    /// The attributes written before the name.
+    pub attrs: Attrs,
+    /// The name of the generated module.
+    pub name: Ident,
+    /// Every state of the machine, together with its memory.
+    ///
+    /// The states are numbered in this order: first as they appear in the
+    /// transitions, then the states only named in other sections as they
+    /// appear there. Moving a section therefore does not renumber them.
+    pub states: StateMems,
+    /// The states declared in the `Init` blocks.
+    ///
+    /// A machine without `Init` block has no `EntryPoint`: it cannot be
+    /// initialised and is only ever restored from storage, with
+    /// `Variants::decode` or by deserializing.
+    pub inits: Inits,
+    /// The states declared in the `Final` blocks.
+    pub exits: Exits,
+    /// The states declared in the `Trace` blocks and the events leaving them.
+    pub traces: Traces,
+    /// The events of the machine and the transitions they trigger.
+    pub events: Events,
+    /// The variants of the generated `Variants` enum.
+    pub variants: MemDefs,
+    /// The snapshot versions declared in the `Migrate` blocks.
+    pub migrations: Migrations
+}
+
+impl Machine {
+    /// Parse a machine definition from source text.
+    ///
+    /// The text has the same grammar as the input of `fsm!`, i.e. the
+    /// machine name followed by a braced block of sections.
+    pub fn parse_str(src: &str) -> Result<Self> {
+        syn::parse_str(src)
+    }
+
+    /// Check the semantic rules that do not follow from the grammar alone.
+    ///
+    /// Errors are combined, so every violation is reported at once.
+    pub fn validate(&self) -> Result<()> {
+        let mut errors: Vec<Error> = Vec::new();
+
+        let declared: Vec<String> = self.inits.iter()
+            .map(|init| &init.state)
+            .chain(self.transitions().flat_map(|transition| [&transition.prev, &transition.next]))
+            .map(|state| state.to_string())
+            .collect();
+
+        let references = self.states.iter()
+            .filter(|state| state.memory.is_some())
+            .map(|state| ("Memory", &state.state))
+            .chain(self.traces.iter().map(|(state, _)| ("Trace", &state.name)))
+            .chain(self.exits.iter().map(|exit| ("Final", &exit.state)));
+
+        for transition in self.transitions() {
+            for state in [&transition.prev, &transition.next] {
+                if self.events.iter().any(|event| event.name == *state) {
+                    let hint = suggest::hint(&state.to_string(), declared.iter().map(String::as_str).filter(|declared| state != declared));
+                    errors.push(Error::new_spanned(state, format!{"{} is used both as an event and as a state{}", state, hint}));
+                }
+            }
+        }
+
+        for (block, state) in references {
+            if !declared.iter().any(|declared| state == declared) {
+                let hint = suggest::hint(&state.to_string(), declared.iter().map(String::as_str));
+                errors.push(Error::new_spanned(state, format!{"State {} in the {} block is not used in Init or any transition{}", state, block, hint}));
+            }
+        }
+
+        let count = self.states.iter().count();
+        if count == 0 {
+            errors.push(Error::new_spanned(&self.name, format!{"Machine {} has no states\n  = help: add at least one event, e.g. `Start: Idle => Running`", self.name}));
+        }
+
+        if count > self.attrs.capacity() {
+            let repr = self.attrs.repr();
+            errors.push(Error::new_spanned(&repr, format!{"Machine has {} states, which do not fit in a StateId of type {}\n  = help: at most {} states fit, use a wider #[repr(...)]", count, repr, self.attrs.capacity()}));
+        }
+
+        let states: Vec<String> = self.states.iter().map(|state| state.state.to_string()).collect();
+
+        for (i, migration) in self.migrations.iter().enumerate() {
+            let version = &migration.version;
+
+            match &self.attrs.version {
+                None => errors.push(Error::new_spanned(version, "Migrate requires the machine to declare its version\n  = help: add #[version(N)] before the machine name")),
+                Some (current) if migration.number() >= current.base10_parse().unwrap_or_default() => {
+                    errors.push(Error::new_spanned(version, format!{"Cannot migrate from version {}, which is not older than the current version {}", version, current}));
+                },
+                Some (_) => {}
+            }
+
+            if let Some(first) = self.migrations.iter().take(i).find(|first| first.number() == migration.number()) {
+                let mut err = Error::new_spanned(version, format!{"Duplicate migration from version {}", version});
+                err.combine(Error::new_spanned(&first.version, "First declared here"));
+                errors.push(err);
+            }
+
+            if migration.states.len() > migration.capacity() {
+                errors.push(Error::new_spanned(version, format!{"Version {} has {} states, which do not fit in a StateId of type {}\n  = help: declare the repr of that version, e.g. `#[repr(u16)] {}`", version, migration.states.len(), migration.repr(), version}));
+            }
+
+            for state in migration.states.iter() {
+                if !states.iter().any(|name| state.target == name) {
+                    let hint = suggest::hint(&state.target.to_string(), states.iter().map(String::as_str));
+                    if state.target == state.state {
+                        errors.push(Error::new_spanned(&state.state, format!{"State {} of version {} is not mapped to a state of the current version{}\n  = help: map it with `{} => State`", state.state, version, hint, state.state}));
+                    } else {
+                        errors.push(Error::new_spanned(&state.target, format!{"State {} of version {} is mapped to unknown state {}{}", state.state, version, state.target, hint}));
+                    }
+                }
+            }
+        }
+
+        for transition in self.events.iter().flat_map(|event| event.transitions.iter()) {
+            if self.exits.iter().any(|exit| exit.state == transition.prev) {
+                errors.push(Error::new_spanned(&transition.prev, format!{"Final state cannot have outgoing transitions: {}", transition.prev}));
+            }
+        }
+
+        // States created by a misspelled reference would also be reported
+        // as unreachable dead ends, which only buries the actual mistake.
+        let references_ok = errors.is_empty();
+
+        if references_ok && !self.attrs.allows("unreachable_states") {
+            let paths = self.paths();
+
+            for state in self.unreachable() {
+                let reachable: Vec<String> = self.states.iter()
+                    .filter(|state| paths.contains_key(&state.state))
+                    .map(|state| state.state.to_string())
+                    .collect();
+                let hint = suggest::hint(&state.state.to_string(), reachable.iter().map(String::as_str));
+
+                errors.push(Error::new_spanned(&state.state, format!{"State {} is unreachable from the Init states{}\n  = help: allow with #[allow(unreachable_states)]", state.state, hint}));
+            }
+        }
+
+        if references_ok && !self.attrs.allows("dead_end_states") {
+            let paths = self.paths();
+
+            for state in self.dead_ends() {
+                let path = match paths.get(&state.state) {
+                    Some (path) => {
+                        let init = path.first().map(|transition| &transition.prev).unwrap_or(&state.state);
+                        let steps: String = path.iter()
+                            .map(|transition| format!{" --{}--> {}", transition.event, transition.next})
+                            .collect();
+
+                        format!{", reached by: {}{}", init, steps}
+                    },
+                    None => String::new()
+                };
+
+                errors.push(Error::new_spanned(&state.state, format!{"State {} is a dead end: it has no outgoing transitions and is not declared Final{}\n  = help: allow with #[allow(dead_end_states)]", state.state, path}));
+            }
+        }
+
+        let mut errors = errors.into_iter();
+        match errors.next() {
+            Some (mut err) => {
+                errors.for_each(|other| err.combine(other));
+                Err(err)
+            },
+            None => Ok (())
+        }
+    }
+
+    /// Collect the diagnostics that do not prevent code generation.
+    pub fn warnings(&self) -> Vec<Warning> {
+        let mut warnings: Vec<Warning> = Vec::new();
+
+        if !self.attrs.allows("single_use_states") {
+            let mentions: Vec<&Ident> = self.inits.iter()
+                .map(|init| &init.state)
+                .chain(self.states.iter().filter(|state| state.memory.is_some()).map(|state| &state.state))
+                .chain(self.traces.iter().map(|(state, _)| &state.name))
+                .chain(self.exits.iter().map(|exit| &exit.state))
+                .chain(self.events.iter().flat_map(|event| event.mentions.iter()))
+                .collect();
+
+            for state in self.states.iter() {
+                let mut uses = mentions.iter().filter(|mention| ***mention == state.state);
+
+                if let (Some (mention), None) = (uses.next(), uses.next()) {
+                    let names: Vec<String> = self.states.iter().map(|state| state.state.to_string()).collect();
+                    let hint = suggest::hint(&state.state.to_string(), names.iter().map(String::as_str));
+
+                    warnings.push(Warning {
+                        span: mention.span(),
+                        message: format!{"State {} appears only once in the machine{}\n  = help: allow with #[allow(single_use_states)]", state.state, hint}
+                    });
+                }
+            }
+        }
+
+        if !self.attrs.allows("similar_events") {
+            for (i, event) in self.events.iter().enumerate() {
+                let names: Vec<String> = self.events.iter().take(i).map(|event| event.name.to_string()).collect();
+
+                if let Some (similar) = suggest::similar(&event.name.to_string(), names.iter().map(String::as_str)) {
+                    warnings.push(Warning {
+                        span: event.name.span(),
+                        message: format!{"Event {} is similar to event {}; did you mean `{}`?\n  = help: allow with #[allow(similar_events)]", event.name, similar, similar}
+                    });
+                }
+            }
+        }
+
+        warnings
+    }
+}
+
+impl Parse for Machine {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let mut states: Vec<State> = Vec::new();
+        let mut transition_states: Vec<State> = Vec::new();
+        let mut events: Vec<Event> = Vec::new();
+        let mut inits: Vec<Init> = Vec::new();
+        let mut exits: Vec<Exit> = Vec::new();
+        let mut trace_states: Vec<(State, Vec<Ident>)> = Vec::new();
+        let mut mem_states: Vec<State> = Vec::new();
+        let mut mem_defs: Vec<MemDef> = Vec::new();
+        let mut migrations: Vec<Migration> = Vec::new();
+
+        let attrs: Attrs = input.parse()?;
+        let name: Ident = input.parse()?;
+
+        let machine_blk;
+        braced!(machine_blk in input);
+
+        if !input.is_empty() {
+            return Err(input.error("Unexpected tokens after parsing."));
+        } 
+
+        while !machine_blk.is_empty() {
+            let fork = machine_blk.fork();
+            let section = match fork.parse::<Ident>() {
+                Ok (section) if fork.peek(Bracket) => section.to_string(),
+                _ => String::new()
+            };
+
+            match section.as_str() {
+                "Init" => {
+                    let blk: Inits = machine_blk.parse()?;
+
+                    for init in blk {
+                        if let Some(first) = inits.iter().find(|first| **first == init) {
+                            return Err(duplicate(&init.state, &first.state, "Duplicate initial state"));
+                        }
+
+                        declare(&mut states, init.clone().into());
+                        inits.push(init);
+                    }
+                },
+                "Memory" => {
+                    let MemDefBlk {
+                        states: blk_states,
+                        mem_defs: blk_defs
+                    } = machine_blk.parse()?;
+
+                    for state in blk_states {
+                        if let Some(first) = mem_states.iter().find(|first| **first == state) {
+                            return Err(duplicate(&state.name, &first.name, "Duplicate memory declaration"));
+                        }
+
+                        declare(&mut states, state.clone());
+                        mem_states.push(state);
+                    }
+
+                    mem_defs.extend(Vec::from(blk_defs));
+                },
+                "Trace" => {
+                    let blk: Traces = machine_blk.parse()?;
+
+                    for (state, _) in blk {
+                        if let Some((first, _)) = trace_states.iter().find(|(first, _)| *first == state) {
+                            return Err(duplicate(&state.name, &first.name, "Duplicate traced state"));
+                        }
+
+                        declare(&mut states, state.clone());
+                        trace_states.push((state, Vec::new()));
+                    }
+                },
+                "Migrate" => {
+                    let blk: Migrations = machine_blk.parse()?;
+
+                    migrations.extend(blk);
+                },
+                "Final" => {
+                    let blk: Exits = machine_blk.parse()?;
+
+                    for exit in blk {
+                        if let Some(first) = exits.iter().find(|first| **first == exit) {
+                            return Err(duplicate(&exit.state, &first.state, "Duplicate final state"));
+                        }
+
+                        declare(&mut states, exit.clone().into());
+                        exits.push(exit);
+                    }
+                },
+                _ => {
+                    let EDefinition {states: event_states, event} = machine_blk.parse()?;
+
+                    if let Some(first) = events.iter().find(|first| **first == event) {
+                        return Err(duplicate(&event.name, &first.name, "Duplicate event"));
+                    }
+
+                    events.push(event);
+                    event_states.into_iter().for_each(|state| {
+                        declare(&mut transition_states, state.clone());
+                        declare(&mut states, state);
+                    });
+                }
+            }
+        }
+
+        for event in events.iter() {
+            for transition in event.transitions.iter() {
+                if let Some((_, events)) = trace_states.iter_mut().find(|(state, _)| state.name == transition.prev) {
+                    events.push(event.name.clone());
+                }
+            }
+        }
+
+        states.sort_by_key(|state| transition_states.iter().position(|first| first == state).unwrap_or(usize::MAX));
+
+        let mem_defs: Vec<MemDef> = states.into_iter()
+            .map(|state| match mem_defs.iter().position(|mem_def| mem_def.state == state.name) {
+                Some (i) => mem_defs.remove(i),
+                None => state.into()
+            })
+            .collect();
+
+        let state_mems: Vec<StateMem> = mem_defs.iter().cloned().map(|md| md.into()).collect();
+
+        Ok (
+            Machine {
+                attrs,
+                name,
+                inits: inits.into(),
+                exits: exits.into(),
+                traces: trace_states.into(),
+                states: state_mems.into(), 
+                events: events.into(),
+                variants: mem_defs.into(),
+                migrations: migrations.into()
+            }
+        )
+    }
+}
+
+/// Add `state` to `states` unless it is already declared.
+fn declare(states: &mut Vec<State>, state: State) {
+    if !states.contains(&state) {
+        states.push(state);
+    }
+}
+
+fn duplicate(name: &Ident, first: &Ident, msg: &str) -> Error {
+    let mut err = Error::new_spanned(name, format!{"{}: {}", msg, name});
+    err.combine(Error::new_spanned(first, "First declared here"));
+    err
+}
+
+impl ToTokens for Machine {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let name = &self.name;
+        let inits = &self.inits;
+        let exits = &self.exits;
+        let traces = &self.traces;
+        let states = &self.states;
+        let events = &self.events;
+        let variants = &self.variants;
+        let repr = self.attrs.repr();
+        let ids: Vec<&Ident> = self.variants.iter().map(|variant| &variant.state).collect();
+        let numbers: Vec<Literal> = (0..ids.len()).map(Literal::usize_unsuffixed).collect();
+        let count = ids.len();
+        let to_u8 = if repr == "u8" {
+            Some (quote! {
+                /// The ID as a `u8`, only generated for `#[repr(u8)]` machines
+                /// since a wider ID may not fit.
+                pub const fn to_u8(self) -> u8 {
+                    self as u8
+                }
+            })
+        } else {
+            None
+        };
+        let serde_variants = crate::serde::variants(&self.variants);
+        let encode = crate::encode::variants(self);
+        let dispatch = crate::dispatch::tokens(self);
+        let scxml = self.scxml();
+        let state_id = crate::serde::derive(quote! {
+            /// The states of the machine as plain numbers, first as they appear in
+            /// the transitions, then as they appear in the other sections.
+            #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+            #[repr(#repr)]
+            pub enum StateId {
+                #(#ids = #numbers,)*
+            }
+        });
+
+        tokens.extend(quote! {
+            #[allow(non_snake_case)]
+            mod #name {
+                pub use static_fsm::{Transition, Init, ToEnum, ToMemEnum};
+
+                use core::marker::PhantomData;
+                use static_fsm::{Event, State, SM, EntryPoint};
+
+                #[derive(Clone)]
+                pub struct FSM<S: State> {
+                    _s: PhantomData<S>,
+                }
+
+                impl<S: State> SM for FSM<S> {
+                    type State = S;
+                }
+
+                impl<S: State + EntryPoint> Init<S> for FSM<S> {
+                    type SM = FSM<S>;
+
+                    fn init() -> Self::SM {
+                        FSM {
+                            _s: PhantomData
+                        }
+                    }
+                }
+
+                #states
+                #inits
+                #exits
+                #events
+                #traces
+                #[repr(#repr)]
+                pub enum Variants {
+                    #variants
+                }
+
+                impl Variants {
+                    /// The discriminant of the variant, the number of its `StateId`.
+                    pub const fn discriminant(&self) -> #repr {
+                        self.id() as #repr
+                    }
+
+                    /// The ID of the state held by the variant.
+                    pub const fn id(&self) -> StateId {
+                        match self {
+                            #(Variants::#ids(..) => StateId::#ids,)*
+                        }
+                    }
+                }
+
+                #serde_variants
+                #encode
+                #dispatch
+
+                #state_id
+
+                impl StateId {
+                    /// The number of states.
+                    pub const COUNT: usize = #count;
+
+                    /// Every ID, in numbering order.
+                    pub const ALL: [StateId; #count] = [#(StateId::#ids),*];
+
+                    /// The ID numbered `value`, if any.
+                    pub const fn from_u8(value: u8) -> Option<Self> {
+                        Self::from_u16(value as u16)
+                    }
+
+                    /// The ID numbered `value`, if any.
+                    pub const fn from_u16(value: u16) -> Option<Self> {
+                        match value {
+                            #(#numbers => Some (StateId::#ids),)*
+                            _ => None
+                        }
+                    }
+
+                    #to_u8
+
+                    /// The ID as a `u16`, which every ID fits in. See `to_u8` for
+                    /// `#[repr(u8)]` machines.
+                    pub const fn to_u16(self) -> u16 {
+                        self as u16
+                    }
+                }
+
+                impl From<StateId> for #repr {
+                    fn from(id: StateId) -> Self {
+                        id as #repr
+                    }
+                }
+
+                /// The machine as a W3C SCXML document.
+                pub const SCXML: &str = #scxml;
+            }
+        });
+    }
+}
@@ -1,8 +1,15 @@
-use proc_macro2::TokenStream;
+use proc_macro2::{
+    Literal,
+    TokenStream
+};
 use quote::{quote, ToTokens};
 use syn::{
     parse::{Parse, ParseStream, Result},
-    token::Comma,
+    token::{
+        Comma,
+        Bracket,
+        Paren
+    },
     Token,
     bracketed,
     parenthesized,
@@ -14,20 +21,25 @@ use syn::{
 
 use std::slice::Iter;
 
-use std::collections::HashSet;
-
-use crate::fsm::state::{
-    States,
-    State
+use crate::{
+    state::{
+        States,
+        State
+    },
+    diagnostic::{self, MEMORY}
 };
 
-pub(crate) struct MemDefs {
+/// The variants of the generated `Variants` enum.
+pub struct MemDefs {
     mem_defs: Vec<MemDef>
 }
 
+/// A variant of the generated `Variants` enum.
 #[derive(Clone)]
-pub(crate) struct MemDef {
+pub struct MemDef {
+    /// The state wrapped by the variant.
     pub state: Ident,
+    /// The memory stored next to the state, if declared in the `Memory` block.
     pub memory: Option<Vec<Type>>
 }
 
@@ -36,12 +48,16 @@ pub(crate) struct MemDefBlk {
     pub mem_defs: MemDefs
 }
 
-pub(crate) struct StateMem {
+/// A state of a machine, together with its memory.
+pub struct StateMem {
+    /// The state.
     pub state: Ident,
+    /// The memory of the state, if declared in the `Memory` block.
     pub memory: Option<Vec<Type>>
 }
 
-pub(crate) struct StateMems {
+/// Every state of a machine, together with its memory.
+pub struct StateMems {
     state_mems: Vec<StateMem>
 }
 
@@ -87,7 +103,13 @@ impl From<Vec<StateMem>> for StateMems {
         }
     }
 }
- 
+
+impl From<StateMems> for Vec<StateMem> {
+    fn from(state_mems: StateMems) -> Self {
+        state_mems.state_mems
+    }
+}
+
 impl From<MemDefs> for Vec<MemDef> {
     fn from(mem_defs: MemDefs) -> Self {
         mem_defs.mem_defs
@@ -96,7 +118,7 @@ impl From<MemDefs> for Vec<MemDef> {
 
 impl Parse for MemDefBlk {
     fn parse(input: ParseStream<'_>) -> Result<Self> {
-        let mut states: HashSet<State> = HashSet::new();
+        let mut states: Vec<State> = Vec::new();
         let mut mem_defs: Vec<MemDef> = Vec::new();
 
         let fork = input.fork();
@@ -105,33 +127,39 @@ impl Parse for MemDefBlk {
 
                 let _: Ident = input.parse()?;
                 let storage_blk;
+                if !input.peek(Bracket) {
+                    return Err(diagnostic::help(input.error("Expected `[`"), MEMORY));
+                }
                 bracketed!(storage_blk in input);
 
                 while !storage_blk.is_empty() {
                     let mut def_states: Vec<Ident> = Vec::new();
 
                     loop {
-                        let state: State = storage_blk.parse()?;
-                        if let Some(first) = states.get(&state) {
+                        let state: State = storage_blk.parse().map_err(|err| diagnostic::help(err, MEMORY))?;
+                        if let Some(first) = states.iter().find(|first| **first == state) {
                             let mut err = Error::new_spanned(&state.name, format!{"Duplicate transition origin: {}", state.name});
-                            err.combine(Error::new_spanned(&first.name, format!{"First declared here"}));
+                            err.combine(Error::new_spanned(&first.name, "First declared here"));
 
                             return Err(err);
                         }
 
-                        states.insert(state.clone());
+                        states.push(state.clone());
                         def_states.push(state.name);
 
                         if storage_blk.peek(Token![,]) {
-                            let _: Comma = storage_blk.parse()?;
+                            let _: Comma = storage_blk.parse().map_err(|err| diagnostic::help(err, MEMORY))?;
                         } else {
                             break;
                         }
                     }
                     let type_blk;
+                    if !storage_blk.peek(Paren) {
+                        return Err(diagnostic::help(storage_blk.error("Expected `(`"), MEMORY));
+                    }
                     parenthesized!(type_blk in storage_blk);
                     
-                    let types: Punctuated<Type, Comma> = Punctuated::parse_terminated(&type_blk)?;
+                    let types: Punctuated<Type, Comma> = Punctuated::parse_terminated(&type_blk).map_err(|err| diagnostic::help(err, MEMORY))?;
 
                     let types: Vec<Type> = types.into_iter().collect();
 
@@ -141,15 +169,15 @@ impl Parse for MemDefBlk {
                         break;
                     }
 
-                    let _: Comma = storage_blk.parse()?;
+                    let _: Comma = storage_blk.parse().map_err(|err| diagnostic::help(err, MEMORY))?;
                 }
             }
         }
 
-        return Ok ( MemDefBlk {
+        Ok ( MemDefBlk {
             states: states.into(),
             mem_defs: mem_defs.into()
-        });    
+        })    
     }
 }
 
@@ -163,15 +191,17 @@ impl MemDef {
 }
 
 impl MemDefs {
+    /// Iterate over the variants.
     #[inline]
-    fn iter(&self) -> Iter<'_, MemDef> {
+    pub fn iter(&self) -> Iter<'_, MemDef> {
         self.mem_defs.iter()
     }
 }
 
 impl StateMems {
+    /// Iterate over the states.
     #[inline]
-    fn iter(&self) -> Iter<'_, StateMem> {
+    pub fn iter(&self) -> Iter<'_, StateMem> {
         self.state_mems.iter()
     }
 }
@@ -185,12 +215,14 @@ impl ToTokens for StateMems {
 impl ToTokens for StateMem {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let state = &self.state;
+        let serde = crate::serde::unit(state);
         if let Some(memory) = &self.memory {
-            let names: Vec<Ident> = (0..memory.len()).into_iter().map(|i| Ident::new(&format!("in_{i}"), proc_macro2::Span::call_site())).collect();
+            let names: Vec<Ident> = (0..memory.len()).map(|i| Ident::new(&format!("in_{i}"), proc_macro2::Span::call_site())).collect();
             tokens.extend(quote! {
                 #[derive(Clone, Copy, PartialEq, Eq)]
                 pub struct #state;
                 impl State for #state {}
+                #serde
 
                 impl FSM<#state> {
                     pub fn to_enum(self, #(#names: #memory),*) -> Variants {
@@ -204,6 +236,7 @@ impl ToTokens for StateMem {
                 #[derive(Clone, Copy, PartialEq, Eq)]
                 pub struct #state;
                 impl State for #state {}
+                #serde
 
                 impl FSM<#state> {
                     pub fn to_enum(self) -> Variants {
@@ -217,22 +250,19 @@ impl ToTokens for StateMem {
 
 impl ToTokens for MemDefs {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        self.iter().for_each(|mem_def| mem_def.to_tokens(tokens));
-    }
-}
-
-impl ToTokens for MemDef {
-    fn to_tokens(&self, tokens: &mut TokenStream) {
-        let state = &self.state;
-
-        if let Some(memory) = &self.memory {
-            tokens.extend(quote! {
-                #state(FSM<#state>,#(#memory),*),
-            });
-        } else {
-            tokens.extend(quote! {
-                #state(FSM<#state>),
-            });
+        for (i, mem_def) in self.iter().enumerate() {
+            let state = &mem_def.state;
+            let discriminant = Literal::usize_unsuffixed(i);
+
+            if let Some(memory) = &mem_def.memory {
+                tokens.extend(quote! {
+                    #state(FSM<#state>,#(#memory),*) = #discriminant,
+                });
+            } else {
+                tokens.extend(quote! {
+                    #state(FSM<#state>) = #discriminant,
+                });
+            }
         }
     }
 }
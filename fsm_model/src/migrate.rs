@@ -0,0 +1,238 @@
+use syn::{
+    parse::{Parse, ParseStream, Result},
+    token::{
+        Brace,
+        Bracket,
+        Comma,
+        Paren
+    },
+    Token,
+    Attribute,
+    braced,
+    bracketed,
+    parenthesized,
+    punctuated::Punctuated,
+    Ident,
+    LitInt,
+    Type,
+    Error
+};
+
+use std::slice::Iter;
+
+use proc_macro2::Span;
+
+use crate::{
+    attr::REPRS,
+    diagnostic::{self, MIGRATE},
+    suggest
+};
+
+/// The migrations declared in the `Migrate` block.
+///
+/// ```text
+/// Migrate[
+///     0 { Closed, Opened (u8) },
+///     #[repr(u8)]
+///     1 { Idle => Closed, Opening (u16) => Opened, Broken }
+/// ]
+/// ```
+pub struct Migrations {
+    migrations: Vec<Migration>
+}
+
+/// How the snapshots of an older version map to the current states.
+pub struct Migration {
+    /// The integer type of the state IDs of that version, set with
+    /// `#[repr(...)]` before the version.
+    pub repr: Option<Ident>,
+    /// The version of the snapshots, 0 for those written before the machine
+    /// declared a version.
+    pub version: LitInt,
+    /// The states of that version, in the order of their IDs.
+    pub states: Vec<Migrated>
+}
+
+/// A state of an older version and the current state it becomes.
+pub struct Migrated {
+    /// The name of the state in the older version.
+    pub state: Ident,
+    /// The memory of the state in the older version, if it had any.
+    pub memory: Option<Vec<Type>>,
+    /// The current state, `state` itself unless mapped with `=>`.
+    pub target: Ident
+}
+
+impl Migrations {
+    /// Iterate over the migrations.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, Migration> {
+        self.migrations.iter()
+    }
+}
+
+impl Migration {
+    /// The version as a number.
+    pub fn number(&self) -> u16 {
+        self.version.base10_parse().unwrap_or_default()
+    }
+
+    /// The type of the state IDs of that version, `u16` unless declared
+    /// otherwise, like the current one.
+    pub fn repr(&self) -> Ident {
+        match &self.repr {
+            Some (repr) => repr.clone(),
+            None => Ident::new("u16", Span::call_site())
+        }
+    }
+
+    /// The largest number of states the state IDs of that version can
+    /// represent.
+    pub fn capacity(&self) -> usize {
+        match &self.repr {
+            Some (repr) if repr == "u8" => u8::MAX as usize + 1,
+            _ => u16::MAX as usize + 1
+        }
+    }
+}
+
+impl From<Vec<Migration>> for Migrations {
+    fn from(migrations: Vec<Migration>) -> Self {
+        Self {
+            migrations
+        }
+    }
+}
+
+impl From<Migrations> for Vec<Migration> {
+    fn from(migrations: Migrations) -> Self {
+        migrations.migrations
+    }
+}
+
+impl IntoIterator for Migrations {
+    type Item = Migration;
+    type IntoIter = std::vec::IntoIter<Migration>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.migrations.into_iter()
+    }
+}
+
+impl Parse for Migrations {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let mut migrations: Vec<Migration> = Vec::new();
+
+        let fork = input.fork();
+        if let Ok (migrate) = fork.parse::<Ident>() {
+            if migrate == "Migrate" {
+                let _: Ident = input.parse()?;
+
+                let migrate_blk;
+                if !input.peek(Bracket) {
+                    return Err(diagnostic::help(input.error("Expected `[`"), MIGRATE));
+                }
+                bracketed!(migrate_blk in input);
+
+                while !migrate_blk.is_empty() {
+                    let migration: Migration = migrate_blk.parse().map_err(|err| diagnostic::help(err, MIGRATE))?;
+                    migrations.push(migration);
+
+                    if migrate_blk.is_empty() {
+                        break;
+                    }
+
+                    let _: Comma = migrate_blk.parse().map_err(|err| diagnostic::help(err, MIGRATE))?;
+                }
+            }
+        }
+
+        Ok ( Migrations {
+            migrations
+        } )
+    }
+}
+
+impl Parse for Migration {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let mut repr: Option<Ident> = None;
+
+        for attr in input.call(Attribute::parse_outer)? {
+            if !attr.path().is_ident("repr") {
+                return Err(Error::new_spanned(attr.path(), "Unknown migration attribute"));
+            }
+
+            let ident: Ident = attr.parse_args()?;
+
+            if !REPRS.iter().any(|known| ident == known) {
+                let hint = suggest::hint(&ident.to_string(), REPRS.iter().copied());
+                return Err(Error::new_spanned(&ident, format!{"Unsupported repr: {}{}", ident, hint}));
+            }
+
+            if let Some(first) = &repr {
+                let mut err = Error::new_spanned(&ident, format!{"Duplicate repr: {}", ident});
+                err.combine(Error::new_spanned(first, "First declared here"));
+
+                return Err(err);
+            }
+
+            repr = Some (ident);
+        }
+
+        let version: LitInt = input.parse()?;
+        version.base10_parse::<u16>()?;
+
+        let states_blk;
+        if !input.peek(Brace) {
+            return Err(input.error("Expected `{`"));
+        }
+        braced!(states_blk in input);
+
+        let states: Punctuated<Migrated, Comma> = Punctuated::parse_terminated(&states_blk)?;
+        let states: Vec<Migrated> = states.into_iter().collect();
+
+        for (i, state) in states.iter().enumerate() {
+            if let Some(first) = states[..i].iter().find(|first| first.state == state.state) {
+                let mut err = Error::new_spanned(&state.state, format!{"Duplicate state in version {}: {}", version, state.state});
+                err.combine(Error::new_spanned(&first.state, "First declared here"));
+
+                return Err(err);
+            }
+        }
+
+        Ok ( Migration {
+            repr,
+            version,
+            states
+        })
+    }
+}
+
+impl Parse for Migrated {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let state: Ident = input.parse()?;
+
+        let memory = if input.peek(Paren) {
+            let type_blk;
+            parenthesized!(type_blk in input);
+
+            let types: Punctuated<Type, Comma> = Punctuated::parse_terminated(&type_blk)?;
+            Some (types.into_iter().collect())
+        } else {
+            None
+        };
+
+        let target = if input.peek(Token![=>]) {
+            let _: Token![=>] = input.parse()?;
+            input.parse()?
+        } else {
+            state.clone()
+        };
+
+        Ok ( Migrated {
+            state,
+            memory,
+            target
+        })
+    }
+}
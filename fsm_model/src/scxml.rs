@@ -0,0 +1,622 @@
+use proc_macro2::{
+    TokenStream,
+    Span
+};
+
+use quote::quote;
+use syn::{
+    parse::{Parser, Result},
+    punctuated::Punctuated,
+    token::Comma,
+    Ident,
+    LitInt,
+    Type,
+    Error
+};
+use roxmltree::{
+    Document,
+    Node
+};
+
+use std::fmt::{
+    Display,
+    Write
+};
+use std::path::Path;
+
+use crate::{
+    attr::REPRS,
+    machine::Machine,
+    memory::{MemDef, StateMem}
+};
+
+const SCXML_NS: &str = "http://www.w3.org/2005/07/scxml";
+const FSM_NS: &str = "https://github.com/lochers/static-fsm";
+
+struct Reader<'a> {
+    origin: &'a str,
+    span: Span,
+    doc: &'a Document<'a>
+}
+
+struct ScxmlTransition<'a> {
+    event: &'a str,
+    orig: &'a str,
+    dest: &'a str,
+    after: Option<&'a str>,
+    node: Node<'a, 'a>
+}
+
+impl<'a> Reader<'a> {
+    fn error(&self, node: Node<'_, '_>, msg: impl Display) -> Error {
+        let pos = self.doc.text_pos_at(node.range().start);
+        Error::new(self.span, format!{"{}:{}:{}: {}", self.origin, pos.row, pos.col, msg})
+    }
+
+    fn ident(&self, node: Node<'_, '_>, name: &str, what: &str) -> Result<Ident> {
+        match syn::parse_str::<Ident>(name) {
+            Ok (_) => Ok (Ident::new(name, self.span)),
+            Err (_) => Err(self.error(node, format!{"{} `{}` is not a valid Rust identifier", what, name}))
+        }
+    }
+
+    fn id(&self, node: Node<'a, 'a>) -> Result<&'a str> {
+        node.attribute("id")
+            .ok_or_else(|| self.error(node, format!{"<{}> without an `id` is not supported", node.tag_name().name()}))
+    }
+
+    fn read(&self) -> Result<Machine> {
+        let scxml = self.doc.root_element();
+
+        if scxml.tag_name().name() != "scxml" {
+            return Err(self.error(scxml, format!{"Expected <scxml> root element, got: <{}>", scxml.tag_name().name()}));
+        }
+
+        let name = match scxml.attribute("name") {
+            Some (name) => name.to_owned(),
+            None => Path::new(self.origin).file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+        let name = self.ident(scxml, &name, "Machine name")?;
+
+        let mut states: Vec<(&str, Node<'_, '_>)> = Vec::new();
+        let mut finals: Vec<&str> = Vec::new();
+        let mut traces: Vec<&str> = Vec::new();
+        let mut memory: Vec<(&str, Node<'_, '_>, TokenStream)> = Vec::new();
+        let mut transitions: Vec<ScxmlTransition<'_>> = Vec::new();
+        let mut migrations: Vec<TokenStream> = Vec::new();
+
+        for child in scxml.children().filter(Node::is_element) {
+            if child.tag_name().name() == "datamodel" {
+                memory.extend(self.datamodel(child)?);
+                continue;
+            }
+
+            if child.tag_name().namespace() == Some (FSM_NS) && child.tag_name().name() == "migrate" {
+                migrations.push(self.migrate(child)?);
+                continue;
+            }
+
+            let id = match child.tag_name().name() {
+                "state" => {
+                    let id = self.id(child)?;
+                    transitions.extend(self.state(child, id)?);
+                    id
+                },
+                "final" => {
+                    let id = self.id(child)?;
+                    if let Some(content) = child.children().find(Node::is_element) {
+                        return Err(self.error(content, format!{"<{}> in a final state is not supported", content.tag_name().name()}));
+                    }
+                    finals.push(id);
+                    id
+                },
+                other => return Err(self.error(child, format!{"<{}> is not supported", other}))
+            };
+
+            if states.iter().any(|(state, _)| *state == id) {
+                return Err(self.error(child, format!{"Duplicate state: {}", id}));
+            }
+
+            match child.attribute((FSM_NS, "trace")) {
+                Some ("true") => traces.push(id),
+                Some ("false") | None => (),
+                Some (other) => return Err(self.error(child, format!{"Expected `true` or `false` for fsm:trace, got: {}", other}))
+            }
+
+            states.push((id, child));
+        }
+
+        if states.is_empty() {
+            return Err(self.error(scxml, "SCXML document declares no states"));
+        }
+
+        // Machines without Init are exported without `initial`, which SCXML
+        // would otherwise read as the first state.
+        let inits: Vec<&str> = match (scxml.attribute("initial"), scxml.attribute((FSM_NS, "init"))) {
+            (None, Some ("false")) => Vec::new(),
+            (Some (_), Some ("false")) => return Err(self.error(scxml, "`initial` contradicts fsm:init=\"false\"")),
+            (_, Some (other)) if other != "false" => return Err(self.error(scxml, format!{"Expected `false` for fsm:init, got: {}", other})),
+            (Some (initial), _) => initial.split_whitespace().collect(),
+            (None, _) => states.iter().take(1).map(|(state, _)| *state).collect()
+        };
+
+        for init in inits.iter() {
+            if !states.iter().any(|(state, _)| state == init) {
+                return Err(self.error(scxml, format!{"Unknown initial state: {}", init}));
+            }
+        }
+
+        for (i, transition) in transitions.iter().enumerate() {
+            if !states.iter().any(|(state, _)| *state == transition.dest) {
+                return Err(self.error(transition.node, format!{"Unknown target state: {}", transition.dest}));
+            }
+
+            if transitions[..i].iter().any(|first| first.event == transition.event && first.orig == transition.orig) {
+                return Err(self.error(transition.node, format!{"Duplicate transition for event {} from state {}", transition.event, transition.orig}));
+            }
+
+            if let Some(first) = transitions[..i].iter().find(|first| first.event == transition.event && first.after != transition.after) {
+                let delay = |after: Option<&str>| match after {
+                    Some (ticks) => format!{"fires after {}", ticks},
+                    None => "has no delay".to_owned()
+                };
+
+                return Err(self.error(transition.node, format!{"Event {} {} here, but {} from state {}", transition.event, delay(transition.after), delay(first.after), first.orig}));
+            }
+        }
+
+        for (state, node) in states.iter() {
+            let connected = inits.contains(state)
+                || finals.contains(state)
+                || transitions.iter().any(|t| t.orig == *state || t.dest == *state);
+
+            if !connected {
+                return Err(self.error(*node, format!{"State {} is not connected to any transition", state}));
+            }
+        }
+
+        let inits = if inits.is_empty() {
+            None
+        } else {
+            let inits = inits.iter()
+                .map(|init| self.ident(scxml, init, "State"))
+                .collect::<Result<Vec<Ident>>>()?;
+
+            Some (quote! { Init[#(#inits),*] })
+        };
+
+        let mut mem_defs: Vec<TokenStream> = Vec::new();
+        for (state, node, types) in memory.iter() {
+            if !states.iter().any(|(s, _)| s == state) {
+                return Err(self.error(*node, format!{"Memory declared for unknown state: {}", state}));
+            }
+
+            let state = self.ident(*node, state, "State")?;
+            mem_defs.push(quote! { #state (#types) });
+        }
+
+        let memory = if mem_defs.is_empty() {
+            None
+        } else {
+            Some (quote! { Memory[#(#mem_defs),*] })
+        };
+
+        let traces = if traces.is_empty() {
+            None
+        } else {
+            let traces = traces.iter()
+                .map(|trace| self.ident(scxml, trace, "State"))
+                .collect::<Result<Vec<Ident>>>()?;
+
+            Some (quote! { Trace[#(#traces),*] })
+        };
+
+        let finals = if finals.is_empty() {
+            None
+        } else {
+            let finals = finals.iter()
+                .map(|exit| self.ident(scxml, exit, "State"))
+                .collect::<Result<Vec<Ident>>>()?;
+
+            Some (quote! { Final[#(#finals),*] })
+        };
+
+        let mut events: Vec<&str> = Vec::new();
+        for transition in transitions.iter() {
+            if !events.contains(&transition.event) {
+                events.push(transition.event);
+            }
+        }
+
+        if let Some(order) = scxml.attribute((FSM_NS, "events")) {
+            let order: Vec<&str> = order.split_whitespace().collect();
+
+            for (i, event) in order.iter().enumerate() {
+                if !events.contains(event) {
+                    return Err(self.error(scxml, format!{"Unknown event in fsm:events: {}", event}));
+                }
+
+                if order[..i].contains(event) {
+                    return Err(self.error(scxml, format!{"Duplicate event in fsm:events: {}", event}));
+                }
+            }
+
+            if let Some(missing) = events.iter().find(|event| !order.contains(event)) {
+                return Err(self.error(scxml, format!{"Event {} is missing from fsm:events", missing}));
+            }
+
+            events = order;
+        }
+
+        let mut defs: Vec<TokenStream> = Vec::new();
+        for event in events {
+            let mut origs: Vec<Ident> = Vec::new();
+            let mut dests: Vec<Ident> = Vec::new();
+            let mut after: Option<TokenStream> = None;
+
+            for transition in transitions.iter().filter(|t| t.event == event) {
+                origs.push(self.ident(transition.node, transition.orig, "State")?);
+                dests.push(self.ident(transition.node, transition.dest, "State")?);
+
+                if let Some(ticks) = transition.after {
+                    let ticks = LitInt::new(ticks, self.span);
+                    after = Some (quote! { after #ticks });
+                }
+            }
+
+            let event = Ident::new(event, self.span);
+            defs.push(quote! {
+                #event #after { #(#origs => #dests),* }
+            });
+        }
+
+        let repr = match scxml.attribute((FSM_NS, "repr")) {
+            Some (repr) => {
+                let repr = self.repr(scxml, repr)?;
+                Some (quote! { #[repr(#repr)] })
+            },
+            None => None
+        };
+
+        let version = match scxml.attribute((FSM_NS, "version")) {
+            Some (version) => {
+                let version = self.version(scxml, version)?;
+                Some (quote! { #[version(#version)] })
+            },
+            None => None
+        };
+
+        let migrations = if migrations.is_empty() {
+            None
+        } else {
+            Some (quote! { Migrate[#(#migrations),*] })
+        };
+
+        let mut machine: Machine = syn::parse2(quote! {
+            #repr
+            #version
+            #name {
+                #inits
+                #memory
+                #traces
+                #finals
+                #(#defs)*
+                #migrations
+            }
+        })?;
+
+        // The DSL numbers states by their first transition, but the document
+        // lists them in the order of their IDs
+        let position = |state: &Ident| states.iter().position(|(id, _)| state == id);
+
+        let mut state_mems: Vec<StateMem> = machine.states.into();
+        state_mems.sort_by_key(|state_mem| position(&state_mem.state));
+        machine.states = state_mems.into();
+
+        let mut variants: Vec<MemDef> = machine.variants.into();
+        variants.sort_by_key(|variant| position(&variant.state));
+        machine.variants = variants.into();
+
+        Ok (machine)
+    }
+
+    fn datamodel(&self, datamodel: Node<'a, 'a>) -> Result<Vec<(&'a str, Node<'a, 'a>, TokenStream)>> {
+        let mut memory = Vec::new();
+
+        for data in datamodel.children().filter(Node::is_element) {
+            let types = match data.attribute((FSM_NS, "memory")) {
+                Some (types) if data.tag_name().name() == "data" => types,
+                _ => return Err(self.error(data, format!{"<{}> without an fsm:memory annotation is not supported", data.tag_name().name()}))
+            };
+
+            if data.has_attribute("expr") || data.has_attribute("src") || data.has_children() {
+                return Err(self.error(data, "Initialised <data> is not supported"));
+            }
+
+            let types = self.types(data, types)?;
+
+            let state = self.id(data)?.strip_suffix(".memory")
+                .ok_or_else(|| self.error(data, "Expected the id of <data> to be `State.memory`"))?;
+
+            memory.push((state, data, types));
+        }
+
+        Ok (memory)
+    }
+
+    fn repr(&self, node: Node<'_, '_>, repr: &str) -> Result<Ident> {
+        if !REPRS.contains(&repr) {
+            return Err(self.error(node, format!{"Unsupported fsm:repr: {}, expected one of: {}", repr, REPRS.join(", ")}));
+        }
+
+        Ok (Ident::new(repr, self.span))
+    }
+
+    fn version(&self, node: Node<'_, '_>, version: &str) -> Result<LitInt> {
+        match version.parse::<u16>() {
+            Ok (_) => Ok (LitInt::new(version, self.span)),
+            Err (_) => Err(self.error(node, format!{"Expected a version number, got: {}", version}))
+        }
+    }
+
+    fn types(&self, node: Node<'_, '_>, types: &str) -> Result<TokenStream> {
+        let types = Punctuated::<Type, Comma>::parse_terminated
+            .parse_str(types)
+            .map_err(|err| self.error(node, format!{"Invalid fsm:memory types `{}`: {}", types, err}))?;
+        let types = types.iter();
+
+        Ok (quote! { #(#types),* })
+    }
+
+    fn migrate(&self, migrate: Node<'a, 'a>) -> Result<TokenStream> {
+        let repr = match migrate.attribute("repr") {
+            Some (repr) => {
+                let repr = self.repr(migrate, repr)?;
+                Some (quote! { #[repr(#repr)] })
+            },
+            None => None
+        };
+
+        let version = migrate.attribute("version")
+            .ok_or_else(|| self.error(migrate, "<fsm:migrate> without a `version` is not supported"))?;
+        let version = self.version(migrate, version)?;
+
+        let mut states: Vec<TokenStream> = Vec::new();
+        for child in migrate.children().filter(Node::is_element) {
+            if child.tag_name().namespace() != Some (FSM_NS) || child.tag_name().name() != "state" {
+                return Err(self.error(child, format!{"<{}> in <fsm:migrate> is not supported", child.tag_name().name()}));
+            }
+
+            let state = self.ident(child, self.id(child)?, "State")?;
+            let memory = match child.attribute("memory") {
+                Some (types) => {
+                    let types = self.types(child, types)?;
+                    Some (quote! { (#types) })
+                },
+                None => None
+            };
+            let target = match child.attribute("target") {
+                Some (target) => {
+                    let target = self.ident(child, target, "State")?;
+                    Some (quote! { => #target })
+                },
+                None => None
+            };
+
+            states.push(quote! { #state #memory #target });
+        }
+
+        Ok (quote! { #repr #version { #(#states),* } })
+    }
+
+    fn state(&self, state: Node<'a, 'a>, id: &'a str) -> Result<Vec<ScxmlTransition<'a>>> {
+        let mut transitions = Vec::new();
+
+        for child in state.children().filter(Node::is_element) {
+            match child.tag_name().name() {
+                "transition" => (),
+                "state" | "parallel" | "final" | "initial" | "history" => {
+                    return Err(self.error(child, format!{"Compound state {} is not supported (nested <{}>)", id, child.tag_name().name()}));
+                },
+                other => return Err(self.error(child, format!{"<{}> is not supported", other}))
+            }
+
+            if child.has_attribute("cond") {
+                return Err(self.error(child, "Conditional transitions (`cond`) are not supported"));
+            }
+
+            if let Some(content) = child.children().find(Node::is_element) {
+                return Err(self.error(content, format!{"Executable content <{}> is not supported", content.tag_name().name()}));
+            }
+
+            let event = child.attribute("event")
+                .ok_or_else(|| self.error(child, "Eventless transitions are not supported"))?;
+            let dest = child.attribute("target")
+                .ok_or_else(|| self.error(child, "Targetless transitions are not supported"))?;
+
+            let dests: Vec<&str> = dest.split_whitespace().collect();
+            if dests.len() != 1 {
+                return Err(self.error(child, "Transitions must have exactly one target"));
+            }
+
+            let after = child.attribute((FSM_NS, "after"));
+            if let Some(ticks) = after {
+                if ticks.parse::<u64>().is_err() {
+                    return Err(self.error(child, format!{"Expected a number of clock ticks for fsm:after, got: {}", ticks}));
+                }
+            }
+
+            for event in event.split_whitespace() {
+                if event.contains('*') {
+                    return Err(self.error(child, format!{"Wildcard event descriptor `{}` is not supported", event}));
+                }
+                self.ident(child, event, "Event")?;
+
+                transitions.push(ScxmlTransition {
+                    event,
+                    orig: id,
+                    dest: dests[0],
+                    after,
+                    node: child
+                });
+            }
+        }
+
+        Ok (transitions)
+    }
+}
+
+impl Machine {
+    /// Import a machine from a W3C SCXML document.
+    ///
+    /// Only the flat subset of SCXML that has a direct counterpart in the DSL
+    /// is accepted: top level `<state>` and `<final>` elements, `<transition>`
+    /// elements with an `event` and a single `target`, and the `initial`
+    /// attribute of the root element. Memory and trace declarations, the
+    /// delays of timed events, the repr, the version and the migrations are
+    /// read back from the `fsm:` annotations written by [`Machine::scxml`].
+    /// Anything else is rejected with an error.
+    ///
+    /// States are numbered in document order. Events are numbered in the
+    /// order of the `fsm:events` annotation if there is one, and by their
+    /// first transition in the document otherwise.
+    ///
+    /// `origin` names the document in error messages and, if the root element
+    /// has no `name`, provides the machine name through its file stem. Every
+    /// error and generated identifier is given `span`.
+    pub fn from_scxml(text: &str, origin: &str, span: Span) -> Result<Self> {
+        let doc = Document::parse(text)
+            .map_err(|err| Error::new(span, format!{"{}: {}", origin, err}))?;
+
+        let reader = Reader {
+            origin,
+            span,
+            doc: &doc
+        };
+
+        reader.read()
+    }
+
+    /// Serialize the machine to a W3C SCXML document.
+    ///
+    /// Memory types are recorded as `fsm:memory` annotations on `<data>`
+    /// elements of the `<datamodel>`, with the id `State.memory`, and traced
+    /// states carry an `fsm:trace="true"` annotation. A machine without
+    /// `Init` has no `initial` attribute but an `fsm:init="false"`
+    /// annotation, and the transitions of a timed event an `fsm:after`
+    /// annotation with its delay in clock ticks. States are written in the order of their IDs and the
+    /// `fsm:events` annotation of `<scxml>` lists the events in declaration
+    /// order, so that they keep their numbers.
+    ///
+    /// `#[repr(...)]` and `#[version(...)]` become the `fsm:repr` and
+    /// `fsm:version` annotations of `<scxml>`, and each migration an
+    /// `<fsm:migrate version="N">` element listing its states as
+    /// `<fsm:state id="Idle" memory="u16" target="Closed"/>`, so that the
+    /// snapshots keep their layout. The document can therefore be read back
+    /// by `fsm_scxml!`.
+    pub fn scxml(&self) -> String {
+        let mut doc = String::new();
+
+        let inits: Vec<String> = self.inits.iter().map(|init| init.state.to_string()).collect();
+        let initial = if inits.is_empty() {
+            r#"fsm:init="false""#.to_owned()
+        } else {
+            format!{r#"initial="{}""#, inits.join(" ")}
+        };
+
+        let events: Vec<String> = self.events.iter().map(|event| event.name.to_string()).collect();
+
+        let mut attrs = String::new();
+        if let Some(repr) = &self.attrs.repr {
+            let _ = write!(attrs, r#" fsm:repr="{}""#, repr);
+        }
+        if let Some(version) = &self.attrs.version {
+            let _ = write!(attrs, r#" fsm:version="{}""#, version.base10_digits());
+        }
+
+        let _ = writeln!(doc, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        let _ = writeln!(doc, r#"<scxml xmlns="{}" xmlns:fsm="{}" version="1.0" name="{}" {} fsm:events="{}"{}>"#, SCXML_NS, FSM_NS, self.name, initial, events.join(" "), attrs);
+
+        for migration in self.migrations.iter() {
+            let repr = match &migration.repr {
+                Some (repr) => format!{r#" repr="{}""#, repr},
+                None => String::new()
+            };
+
+            let _ = writeln!(doc, r#"  <fsm:migrate version="{}"{}>"#, migration.version.base10_digits(), repr);
+            for migrated in migration.states.iter() {
+                let mut state = format!{r#"    <fsm:state id="{}""#, migrated.state};
+                if let Some(memory) = &migrated.memory {
+                    let types: Vec<String> = memory.iter().map(|ty| quote!(#ty).to_string()).collect();
+                    let _ = write!(state, r#" memory="{}""#, escape(&types.join(", ")));
+                }
+                if migrated.target != migrated.state {
+                    let _ = write!(state, r#" target="{}""#, migrated.target);
+                }
+                let _ = writeln!(doc, "{}/>", state);
+            }
+            let _ = writeln!(doc, "  </fsm:migrate>");
+        }
+
+        if self.states.iter().any(|state| state.memory.is_some()) {
+            let _ = writeln!(doc, "  <datamodel>");
+            for state in self.states.iter() {
+                if let Some(memory) = &state.memory {
+                    let types: Vec<String> = memory.iter().map(|ty| quote!(#ty).to_string()).collect();
+                    let _ = writeln!(doc, r#"    <data id="{}.memory" fsm:memory="{}"/>"#, state.state, escape(&types.join(", ")));
+                }
+            }
+            let _ = writeln!(doc, "  </datamodel>");
+        }
+
+        for state in self.states.iter() {
+            let tag = if self.exits.iter().any(|exit| exit.state == state.state) {
+                "final"
+            } else {
+                "state"
+            };
+
+            let trace = if self.traces.iter().any(|(trace, _)| trace.name == state.state) {
+                r#" fsm:trace="true""#
+            } else {
+                ""
+            };
+
+            let transitions: Vec<String> = self.events.iter()
+                .flat_map(|event| event.transitions.iter().map(move |transition| (event, transition)))
+                .filter(|(_, transition)| transition.prev == state.state)
+                .map(|(event, transition)| {
+                    let after = match event.delay() {
+                        Some (delay) => format!{r#" fsm:after="{}""#, delay},
+                        None => String::new()
+                    };
+
+                    format!{r#"    <transition event="{}" target="{}"{}/>"#, transition.event, transition.next, after}
+                })
+                .collect();
+
+            if transitions.is_empty() {
+                let _ = writeln!(doc, r#"  <{} id="{}"{}/>"#, tag, state.state, trace);
+            } else {
+                let _ = writeln!(doc, r#"  <{} id="{}"{}>"#, tag, state.state, trace);
+                for transition in transitions {
+                    let _ = writeln!(doc, "{}", transition);
+                }
+                let _ = writeln!(doc, "  </{}>", tag);
+            }
+        }
+
+        let _ = writeln!(doc, "</scxml>");
+
+        doc
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
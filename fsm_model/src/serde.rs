@@ -0,0 +1,158 @@
+//! Code generation for the `serde` feature.
+//!
+//! States and events serialize by name, `StateId` as a unit variant and
+//! `Variants` as an enum tagged with the state name whose content is the
+//! memory of the state. The typestate marker `FSM<S>` is not serialized, it
+//! is rebuilt from the tag on deserialization.
+//!
+//! The implementations are wrapped in the `static_fsm::__private` macros,
+//! which drop them unless the `serde` feature of `static-fsm` is enabled.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    Ident,
+    Type
+};
+
+use crate::memory::MemDefs;
+
+/// The generated type `item`, deriving `Serialize` and `Deserialize`.
+pub(crate) fn derive(item: TokenStream) -> TokenStream {
+    quote! {
+        static_fsm::__private::serde_derive! {
+            #item
+        }
+    }
+}
+
+/// `Serialize` and `Deserialize` for the unit struct `name`, by name.
+pub(crate) fn unit(name: &Ident) -> TokenStream {
+    quote! {
+        static_fsm::__private::serde! {
+            const _: () = {
+                #[derive(static_fsm::serde::Serialize, static_fsm::serde::Deserialize)]
+                #[serde(crate = "static_fsm::serde")]
+                enum __Name {
+                    #name
+                }
+
+                impl static_fsm::serde::Serialize for #name {
+                    fn serialize<__S>(&self, serializer: __S) -> Result<__S::Ok, __S::Error>
+                    where
+                        __S: static_fsm::serde::Serializer
+                    {
+                        static_fsm::serde::Serialize::serialize(&__Name::#name, serializer)
+                    }
+                }
+
+                impl<'de> static_fsm::serde::Deserialize<'de> for #name {
+                    fn deserialize<__D>(deserializer: __D) -> Result<Self, __D::Error>
+                    where
+                        __D: static_fsm::serde::Deserializer<'de>
+                    {
+                        <__Name as static_fsm::serde::Deserialize>::deserialize(deserializer).map(|_| #name)
+                    }
+                }
+            };
+        }
+    }
+}
+
+/// `Serialize` and `Deserialize` for the `Variants` enum.
+pub(crate) fn variants(variants: &MemDefs) -> TokenStream {
+    // The helper enums are generic over the memory types, so the bounds on
+    // them are only checked when `Variants` is (de)serialized.
+    let types: Vec<&Type> = variants.iter()
+        .flat_map(|variant| variant.memory.iter().flatten())
+        .collect();
+    let params: Vec<Ident> = (0..types.len()).map(|i| format_ident!("__T{}", i)).collect();
+    let generics = if types.is_empty() {
+        None
+    } else {
+        Some (quote!(<#(#params),*>))
+    };
+    let ref_generics = if types.is_empty() {
+        None
+    } else {
+        Some (quote!(<'a, #(#params),*>))
+    };
+
+    let mut refs: Vec<TokenStream> = Vec::new();
+    let mut owned: Vec<TokenStream> = Vec::new();
+    let mut to_ref: Vec<TokenStream> = Vec::new();
+    let mut from_owned: Vec<TokenStream> = Vec::new();
+    let mut param = params.iter();
+
+    for variant in variants.iter() {
+        let state = &variant.state;
+
+        match &variant.memory {
+            Some (memory) => {
+                let names: Vec<Ident> = (0..memory.len()).map(|i| format_ident!("in_{}", i)).collect();
+                let params: Vec<&Ident> = param.by_ref().take(memory.len()).collect();
+
+                refs.push(quote!(#state(#(&'a #params),*)));
+                owned.push(quote!(#state(#(#params),*)));
+                to_ref.push(quote!(Variants::#state(_, #(#names),*) => __Ref::#state(#(#names),*)));
+                from_owned.push(quote!(__Owned::#state(#(#names),*) => Variants::#state(FSM { _s: PhantomData }, #(#names),*)));
+            },
+            None => {
+                refs.push(quote!(#state));
+                owned.push(quote!(#state));
+                to_ref.push(quote!(Variants::#state(_) => __Ref::#state));
+                from_owned.push(quote!(__Owned::#state => Variants::#state(FSM { _s: PhantomData })));
+            }
+        }
+    }
+
+    quote! {
+        static_fsm::__private::serde! {
+            const _: () = {
+                #[derive(static_fsm::serde::Serialize)]
+                #[serde(crate = "static_fsm::serde", rename = "Variants")]
+                enum __Ref #ref_generics {
+                    #(#refs),*
+                }
+
+                #[derive(static_fsm::serde::Deserialize)]
+                #[serde(crate = "static_fsm::serde", rename = "Variants")]
+                enum __Owned #generics {
+                    #(#owned),*
+                }
+
+                impl static_fsm::serde::Serialize for Variants
+                where
+                    #(for<'a> #types: static_fsm::serde::Serialize,)*
+                {
+                    fn serialize<__S>(&self, serializer: __S) -> Result<__S::Ok, __S::Error>
+                    where
+                        __S: static_fsm::serde::Serializer
+                    {
+                        let variant: __Ref<#(#types),*> = match self {
+                            #(#to_ref),*
+                        };
+
+                        static_fsm::serde::Serialize::serialize(&variant, serializer)
+                    }
+                }
+
+                impl<'de> static_fsm::serde::Deserialize<'de> for Variants
+                where
+                    #(#types: static_fsm::serde::Deserialize<'de>,)*
+                {
+                    fn deserialize<__D>(deserializer: __D) -> Result<Self, __D::Error>
+                    where
+                        __D: static_fsm::serde::Deserializer<'de>
+                    {
+                        let variant = <__Owned<#(#types),*> as static_fsm::serde::Deserialize>::deserialize(deserializer)?;
+
+                        Ok (match variant {
+                            #(#from_owned),*
+                        })
+                    }
+                }
+            };
+        }
+    }
+}
@@ -10,20 +10,20 @@ use std::hash::{
     Hash
 };
 
-use std::collections::HashSet;
 
-pub(crate) struct States {
-    states: HashSet<State>
+/// A list of states.
+pub struct States {
+    states: Vec<State>
 }
 
-impl Into<HashSet<State>> for States {
-    fn into(self) -> HashSet<State> {
-        self.states
+impl From<States> for Vec<State> {
+    fn from(states: States) -> Self {
+        states.states
     }
 }
 
-impl From<HashSet<State>> for States {
-    fn from(states: HashSet<State>) -> Self {
+impl From<Vec<State>> for States {
+    fn from(states: Vec<State>) -> Self {
         Self {
             states
         }
@@ -40,15 +40,17 @@ impl From<Ident> for State {
 
 impl IntoIterator for States {
     type Item = State;
-    type IntoIter = std::collections::hash_set::IntoIter<State>;
+    type IntoIter = std::vec::IntoIter<State>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.states.into_iter()
     }
 }
 
+/// A state of a machine.
 #[derive(Clone)]
-pub(crate) struct State {
+pub struct State {
+    /// The name of the state struct.
     pub name: Ident
 }
 
@@ -0,0 +1,68 @@
+/// Find the candidate closest to `name`, if any is close enough to be a typo.
+pub(crate) fn closest<'a, I>(name: &str, candidates: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>
+{
+    let max = usize::max(1, name.chars().count() / 3);
+
+    candidates.into_iter()
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| (distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Format a "did you mean" hint for `name`, or nothing.
+pub(crate) fn hint<'a, I>(name: &str, candidates: I) -> String
+where
+    I: IntoIterator<Item = &'a str>
+{
+    match closest(name, candidates) {
+        Some (candidate) => format!{"; did you mean `{}`?", candidate},
+        None => String::new()
+    }
+}
+
+/// Find an earlier name that `name` is likely a misspelling of.
+///
+/// Stricter than [`closest`]: short names such as `Get` and `Set` are
+/// legitimately one edit apart, so only one edit per four characters is
+/// tolerated, and a name extending another, such as `Connected` and
+/// `Connect`, is deliberate.
+pub(crate) fn similar<'a, I>(name: &str, candidates: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>
+{
+    let max = name.chars().count() / 4;
+
+    candidates.into_iter()
+        .filter(|candidate| !name.starts_with(candidate) && !candidate.starts_with(name))
+        .map(|candidate| (distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// The edit distance between two strings, counting the transposition of two
+/// adjacent characters as a single edit.
+fn distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d: Vec<Vec<usize>> = (0..=a.len())
+        .map(|i| (0..=b.len()).map(|j| if i == 0 { j } else if j == 0 { i } else { 0 }).collect())
+        .collect();
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = usize::min(d[i - 1][j - 1] + cost, usize::min(d[i - 1][j], d[i][j - 1]) + 1);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = usize::min(d[i][j], d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    d[a.len()][b.len()]
+}
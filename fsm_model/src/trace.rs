@@ -7,38 +7,43 @@ use quote::{quote, ToTokens};
 use syn::{
     bracketed,
     parse::{Parse, ParseStream, Result},
-    token::Comma,
+    token::{
+        Comma,
+        Bracket
+    },
     Ident,
     Error
 };
 
-use std::collections::{
-    HashSet,
-    HashMap,
-    hash_map::Iter,
-};
+use std::slice::Iter;
 
-use crate::fsm::state::State;
+use crate::{
+    state::State,
+    diagnostic::{self, TRACE}
+};
 
-pub(crate) struct Traces {
-    pub traces: HashMap<State, Vec<Ident>>
+/// The states declared in the `Trace` block.
+pub struct Traces {
+    /// Each traced state and the events leaving it.
+    pub traces: Vec<(State, Vec<Ident>)>
 }
 
 impl Traces {
+    /// Iterate over the traced states and the events leaving them.
     #[inline]
-    pub(crate) fn iter(&self) -> Iter<'_, State, Vec<Ident>> {
+    pub fn iter(&self) -> Iter<'_, (State, Vec<Ident>)> {
         self.traces.iter()
     }
 }
 
-impl Into<HashMap<State, Vec<Ident>>> for Traces {
-    fn into(self) -> HashMap<State, Vec<Ident>> {
-        self.traces
+impl From<Traces> for Vec<(State, Vec<Ident>)> {
+    fn from(traces: Traces) -> Self {
+        traces.traces
     }
 }
 
-impl From<HashMap<State, Vec<Ident>>> for Traces {
-    fn from(traces: HashMap<State, Vec<Ident>>) -> Self {
+impl From<Vec<(State, Vec<Ident>)>> for Traces {
+    fn from(traces: Vec<(State, Vec<Ident>)>) -> Self {
         Self {
             traces
         }
@@ -47,7 +52,7 @@ impl From<HashMap<State, Vec<Ident>>> for Traces {
 
 impl IntoIterator for Traces {
     type Item = (State, Vec<Ident>);
-    type IntoIter = std::collections::hash_map::IntoIter<State, Vec<Ident>>;
+    type IntoIter = std::vec::IntoIter<(State, Vec<Ident>)>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.traces.into_iter()
@@ -57,8 +62,7 @@ impl IntoIterator for Traces {
 
 impl Parse for Traces {
     fn parse(input: ParseStream<'_>) -> Result<Self> {
-        let mut trace_ref: HashSet<State> = HashSet::new();
-        let mut traces: HashMap<State, Vec<Ident>> = HashMap::new();
+        let mut traces: Vec<(State, Vec<Ident>)> = Vec::new();
 
         let fork = input.fork();
         if let Ok (memory) = fork.parse::<Ident>() {
@@ -66,27 +70,28 @@ impl Parse for Traces {
                 let _:Ident = input.parse()?;
 
                 let trace_blk;
+                if !input.peek(Bracket) {
+                    return Err(diagnostic::help(input.error("Expected `[`"), TRACE));
+                }
                 bracketed!(trace_blk in input);
 
                 while !trace_blk.is_empty() {
-                    let state: State = trace_blk.parse()?;
+                    let state: State = trace_blk.parse().map_err(|err| diagnostic::help(err, TRACE))?;
                     
-                    if let Some(first_trace) = trace_ref.get(&state) {
-                        let mut err = Error::new_spanned(&state.name, format!{"Duplicate sate traceialisation: {}", state.name});
-                        err.combine(Error::new_spanned(&first_trace.name, format!{"First declared here"}));
+                    if let Some((first_trace, _)) = traces.iter().find(|(first, _)| *first == state) {
+                        let mut err = Error::new_spanned(&state.name, format!{"Duplicate traced state: {}", state.name});
+                        err.combine(Error::new_spanned(&first_trace.name, "First declared here"));
 
                         return Err(err);
                     }
 
-                    trace_ref.insert(state.clone());
-
-                    traces.insert(state, Vec::new());
+                    traces.push((state, Vec::new()));
 
                     if trace_blk.is_empty() {
                         break;
                     }
 
-                    let _: Comma = trace_blk.parse()?;
+                    let _: Comma = trace_blk.parse().map_err(|err| diagnostic::help(err, TRACE))?;
                 }
             }
         }
@@ -5,7 +5,8 @@ use syn::Ident;
 
 use std::slice::Iter;
 
-pub(crate) struct Transitions {
+/// The transitions triggered by an event.
+pub struct Transitions {
     transitions: Vec<Transition>
 }
 
@@ -18,9 +19,9 @@ impl IntoIterator for Transitions {
     }
 }
 
-impl Into<Vec<Transition>> for Transitions {
-    fn into(self) -> Vec<Transition> {
-        self.transitions
+impl From<Transitions> for Vec<Transition> {
+    fn from(transitions: Transitions) -> Self {
+        transitions.transitions
     }
 }
 
@@ -32,18 +33,24 @@ impl From<Vec<Transition>> for Transitions {
     }
 }
 
-pub(crate) struct Transition {
+/// A transition between two states.
+pub struct Transition {
+    /// The event triggering the transition.
     pub event: Ident,
+    /// The destination state.
     pub next: Ident,
+    /// The origin state.
     pub prev: Ident
 }
 
 impl Transitions {
+    /// Iterate over the transitions.
     #[inline]
-    fn iter(&self) -> Iter<'_, Transition> {
+    pub fn iter(&self) -> Iter<'_, Transition> {
         self.transitions.iter()
     }
 
+    /// Create a transition from each of `prevs` to `next` on `event`.
     pub fn generate<I>(prevs: I, next: Ident, event: Ident) -> Self
     where
         I: IntoIterator<Item = Ident>
@@ -0,0 +1,86 @@
+use fsm_model::Machine;
+
+fn error(src: &str) -> String {
+    match Machine::parse_str(src) {
+        Ok (_) => panic!("{} parsed", src),
+        Err (err) => err.to_string()
+    }
+}
+
+fn warnings(src: &str) -> Vec<String> {
+    Machine::parse_str(src).unwrap().warnings().into_iter().map(|warning| warning.message).collect()
+}
+
+#[test]
+fn suggests_section_names() {
+    assert_eq!(error("door { Inti[Closed] Open: Closed => Opened }"), "Unknown section: Inti; did you mean `Init`?");
+}
+
+#[test]
+fn warns_about_single_use_states() {
+    assert_eq!(warnings("door { Init[Closed] Open: Closed => Opened Close: Opened => Closed Kick: Opened => Brokn Fix: Broken => Closed }"), [
+        "State Brokn appears only once in the machine; did you mean `Broken`?\n  = help: allow with #[allow(single_use_states)]",
+        "State Broken appears only once in the machine; did you mean `Brokn`?\n  = help: allow with #[allow(single_use_states)]"
+    ]);
+}
+
+#[test]
+fn counts_mentions_as_written() {
+    let src = "door { Init[Closed] Open: Closed => Opened Close: Opened => Closed Kick: Closed, Opened => Brokn }";
+
+    assert_eq!(warnings(src), [
+        "State Brokn appears only once in the machine\n  = help: allow with #[allow(single_use_states)]"
+    ]);
+}
+
+#[test]
+fn allows_single_use_states() {
+    assert!(warnings("#[allow(single_use_states)] door { Init[Closed] Open: Closed => Opened Close: Opened => Closed Kick: Opened => Broken }").is_empty());
+}
+
+#[test]
+fn warns_about_similar_events() {
+    assert_eq!(warnings("door { Init[Closed] Open: Closed => Opened Close: Opened => Closed Opne: Closed => Opened }"), [
+        "Event Opne is similar to event Open; did you mean `Open`?\n  = help: allow with #[allow(similar_events)]"
+    ]);
+}
+
+#[test]
+fn tolerates_short_events_one_edit_apart() {
+    assert!(warnings("door { Init[Closed] Get: Closed => Opened Set: Opened => Closed }").is_empty());
+}
+
+#[test]
+fn tolerates_events_extending_others() {
+    assert!(warnings("link { Init[Idle] Connect: Idle => Connecting Connected: Connecting => Online Drop: Online => Idle }").is_empty());
+}
+
+#[test]
+fn allows_similar_events() {
+    assert!(warnings("#[allow(similar_events)] door { Init[Closed] Open: Closed => Opened Close: Opened => Closed Opne: Closed => Opened }").is_empty());
+}
+
+#[test]
+fn shows_the_grammar_of_malformed_sections() {
+    assert_eq!(error("door { Init[Closed Opened] Open: Closed => Opened }"), "expected `,`\n  = help: expected `Init[State, ...]`");
+}
+
+#[test]
+fn rejects_version_0() {
+    assert_eq!(error("#[version(0)] door { Init[Closed] Open: Closed => Opened }"), "Version 0 stands for the snapshots written before the machine declared a version\n  = help: start at #[version(1)]");
+}
+
+#[test]
+fn parses_the_repr_of_older_versions() {
+    let machine = Machine::parse_str("#[version(2)] door { Init[Closed] Open: Closed => Opened Close: Opened => Closed Migrate[0 { Closed }, #[repr(u8)] 1 { Closed, Opened }] }").unwrap();
+    let reprs: Vec<String> = machine.migrations.iter().map(|migration| migration.repr().to_string()).collect();
+
+    assert_eq!(reprs, ["u16", "u8"]);
+    assert!(machine.validate().is_ok());
+}
+
+#[test]
+fn rejects_unknown_migration_attributes() {
+    assert_eq!(error("#[version(2)] door { Init[Closed] Open: Closed => Opened Migrate[#[allow(x)] 1 { Closed }] }"), "Unknown migration attribute\n  = help: expected `Migrate[[#[repr(u8|u16)]] Version { State (Type, ...) => State, ... }, ...]`");
+    assert_eq!(error("#[version(2)] door { Init[Closed] Open: Closed => Opened Migrate[#[repr(u9)] 1 { Closed }] }"), "Unsupported repr: u9; did you mean `u8`?\n  = help: expected `Migrate[[#[repr(u8|u16)]] Version { State (Type, ...) => State, ... }, ...]`");
+}
@@ -0,0 +1,67 @@
+use fsm_model::Machine;
+use quote::ToTokens;
+
+const DOOR: &str = "door {
+    Init[Closed]
+    Memory[Opened (u32, bool)]
+    Trace[Opened]
+    Final[Broken]
+    Open: Closed => Opened
+    Close { Opened => Closed }
+    Kick: Closed, Opened => Broken
+}";
+
+fn names<'a>(idents: impl Iterator<Item = &'a syn::Ident>) -> Vec<String> {
+    idents.map(|ident| ident.to_string()).collect()
+}
+
+#[test]
+fn parses_the_ir() {
+    let machine = Machine::parse_str(DOOR).unwrap();
+
+    assert_eq!(machine.name, "door");
+    assert_eq!(names(machine.inits.iter().map(|init| &init.state)), ["Closed"]);
+    assert_eq!(names(machine.exits.iter().map(|exit| &exit.state)), ["Broken"]);
+    assert_eq!(names(machine.events.iter().map(|event| &event.name)), ["Open", "Close", "Kick"]);
+
+    let (trace, events) = machine.traces.iter().next().unwrap();
+    assert_eq!(trace.name, "Opened");
+    assert_eq!(names(events.iter()), ["Close", "Kick"]);
+
+    let opened = machine.states.iter().find(|state| state.state == "Opened").unwrap();
+    let memory: Vec<String> = opened.memory.iter().flatten().map(|ty| ty.to_token_stream().to_string()).collect();
+    assert_eq!(memory, ["u32", "bool"]);
+
+    let kick = machine.events.iter().find(|event| event.name == "Kick").unwrap();
+    assert_eq!(names(kick.transitions.iter().map(|transition| &transition.prev)), ["Closed", "Opened"]);
+    assert_eq!(names(kick.transitions.iter().map(|transition| &transition.next)), ["Broken", "Broken"]);
+}
+
+#[test]
+fn validates() {
+    Machine::parse_str(DOOR).unwrap().validate().unwrap();
+}
+
+#[test]
+fn analyses_reachability() {
+    let machine = Machine::parse_str("#[allow(unreachable_states, dead_end_states)] m { Init[A] Go: A => B Back: C => A }").unwrap();
+    let paths = machine.paths();
+
+    assert_eq!(names(machine.entries().into_iter()), ["A"]);
+    assert!(paths[&machine.inits.iter().next().unwrap().state].is_empty());
+    assert_eq!(names(machine.unreachable().into_iter().map(|state| &state.state)), ["C"]);
+    assert_eq!(names(machine.dead_ends().into_iter().map(|state| &state.state)), ["B"]);
+}
+
+#[test]
+fn rejects_malformed_definitions() {
+    let err = |src: &str| Machine::parse_str(src).err().expect("the definition was accepted").to_string();
+
+    assert!(err("door { Open: Closed }").contains("expected `=>`"));
+    assert!(err("door { Init[Closed] Init[Closed] Open: Closed => Opened }").contains("Duplicate initial state: Closed"));
+    assert!(err("door { Init[Closed, Closed] Open: Closed => Opened }").contains("Duplicate initial state: Closed"));
+    assert!(err("door { Init[Closed] Trace[Opened] Trace[Opened] Open: Closed => Opened }").contains("Duplicate traced state: Opened"));
+    assert!(err("door { Init[Closed] Trace[Opened, Opened] Open: Closed => Opened }").contains("Duplicate traced state: Opened"));
+    assert!(err("door { Open: Closed => Opened Open: Opened => Closed }").contains("Duplicate event: Open"));
+    assert!(err("door { } extra").contains("Unexpected tokens after parsing."));
+}
@@ -0,0 +1,268 @@
+use fsm_model::Machine;
+use proc_macro2::Span;
+
+fn import(body: &str) -> syn::Result<Machine> {
+    let text = format!{r#"<scxml xmlns="http://www.w3.org/2005/07/scxml" version="1.0" name="door" initial="Closed">{}</scxml>"#, body};
+    Machine::from_scxml(&text, "door.scxml", Span::call_site())
+}
+
+fn error(body: &str) -> String {
+    match import(body) {
+        Ok (_) => panic!("the document was accepted"),
+        Err (err) => err.to_string()
+    }
+}
+
+#[test]
+fn maps_states_transitions_initial_and_final() {
+    let machine = import(r#"
+        <state id="Closed"><transition event="Open" target="Opened"/></state>
+        <state id="Opened"><transition event="Close Slam" target="Closed"/></state>
+        <final id="Broken"/>
+    "#).unwrap();
+
+    let mut states: Vec<String> = machine.states.iter().map(|state| state.state.to_string()).collect();
+    states.sort();
+    let inits: Vec<String> = machine.inits.iter().map(|init| init.state.to_string()).collect();
+    let exits: Vec<String> = machine.exits.iter().map(|exit| exit.state.to_string()).collect();
+    let transitions: Vec<String> = machine.transitions()
+        .map(|transition| format!{"{} --{}--> {}", transition.prev, transition.event, transition.next})
+        .collect();
+
+    assert_eq!(machine.name, "door");
+    assert_eq!(states, ["Broken", "Closed", "Opened"]);
+    assert_eq!(inits, ["Closed"]);
+    assert_eq!(exits, ["Broken"]);
+    assert_eq!(transitions, ["Closed --Open--> Opened", "Opened --Close--> Closed", "Opened --Slam--> Closed"]);
+}
+
+#[test]
+fn rejects_unsupported_constructs() {
+    assert!(error(r#"<state id="Closed"><state id="Inner"/></state>"#).contains("Compound state Closed is not supported"));
+    assert!(error(r#"<state id="Closed"><transition event="Open" cond="x" target="Closed"/></state>"#).contains("Conditional transitions"));
+    assert!(error(r#"<state id="Closed"><transition event="*" target="Closed"/></state>"#).contains("Wildcard event descriptor"));
+    assert!(error(r#"<state id="Closed"><transition target="Closed"/></state>"#).contains("Eventless transitions"));
+    assert!(error(r#"<parallel id="Closed"/>"#).contains("<parallel> is not supported"));
+}
+
+#[test]
+fn reports_positions_in_the_document() {
+    let err = error(r#"<state id="Closed"><transition event="Open" target="Opened"/></state>"#);
+
+    assert_eq!(err, "door.scxml:1:110: Unknown target state: Opened");
+}
+
+// Everything SCXML preserves, states and events in the order of their
+// numbers. The transitions of an event are grouped by state in SCXML, so
+// their order within the event is not compared.
+fn describe(machine: &Machine) -> Vec<String> {
+    let transitions = machine.events.iter().flat_map(|event| {
+        let mut transitions: Vec<String> = event.transitions.iter()
+            .map(|transition| format!{"{} --{}--> {}", transition.prev, transition.event, transition.next})
+            .collect();
+        transitions.sort();
+        transitions
+    });
+
+    let states = machine.states.iter().map(|state| {
+        let memory = state.memory.as_ref().map(|memory| quote::quote!(#(#memory),*).to_string());
+        format!{"state {} {:?}", state.state, memory}
+    });
+
+    let attrs = [
+        format!{"repr {:?}", machine.attrs.repr.as_ref().map(ToString::to_string)},
+        format!{"version {:?}", machine.attrs.version.as_ref().map(|version| version.base10_digits().to_owned())}
+    ];
+
+    let migrations = machine.migrations.iter().map(|migration| {
+        let states: Vec<String> = migration.states.iter().map(|migrated| {
+            let memory = migrated.memory.as_ref().map(|memory| quote::quote!(#(#memory),*).to_string());
+            format!{"{} {:?} => {}", migrated.state, memory, migrated.target}
+        }).collect();
+
+        format!{"migrate {} {:?} {}", migration.number(), migration.repr.as_ref().map(ToString::to_string), states.join(", ")}
+    });
+
+    let delays = machine.events.iter().map(|event| format!{"event {} after {:?}", event.name, event.delay()});
+
+    attrs.into_iter()
+        .chain(states)
+        .chain(machine.inits.iter().map(|init| format!{"init {}", init.state}))
+        .chain(machine.exits.iter().map(|exit| format!{"final {}", exit.state}))
+        .chain(machine.traces.iter().map(|(state, _)| format!{"trace {}", state.name}))
+        .chain(delays)
+        .chain(transitions)
+        .chain(migrations)
+        .collect()
+}
+
+fn round_trip(src: &str) {
+    let machine = Machine::parse_str(src).unwrap();
+    let exported = machine.scxml();
+    let imported = Machine::from_scxml(&exported, "door.scxml", Span::call_site()).unwrap();
+
+    imported.validate().unwrap();
+    assert_eq!(imported.name, machine.name);
+    assert_eq!(describe(&imported), describe(&machine));
+}
+
+#[test]
+fn export_then_import_round_trips() {
+    round_trip("door {
+        Init[Closed]
+        Memory[Opened (u32, Option<u8>)]
+        Trace[Opened]
+        Final[Broken]
+        Open: Closed => Opened
+        Close: Opened => Closed
+        Kick: Closed, Opened => Broken
+    }");
+}
+
+#[test]
+fn export_then_import_keeps_the_numbers() {
+    round_trip("numbered {
+        Init[P, R]
+        Final[S]
+        E1: P => Q
+        E2: R => P
+        E3: Q => S
+    }");
+
+    round_trip("door {
+        Init[Closed]
+        Trace[Watched]
+        Final[Broken]
+        Kick: Opened, Closed => Broken
+        Open: Closed => Opened
+        Close: Opened => Closed
+        Watch: Closed => Watched
+        Unwatch: Watched => Closed
+    }");
+}
+
+#[test]
+fn numbers_states_in_document_order_and_events_as_annotated() {
+    let text = r#"<scxml xmlns="http://www.w3.org/2005/07/scxml" xmlns:fsm="https://github.com/lochers/static-fsm" version="1.0" name="door" initial="Opened" fsm:events="Close Open">
+        <state id="Closed"><transition event="Open" target="Opened"/></state>
+        <state id="Opened"><transition event="Close" target="Closed"/></state>
+    </scxml>"#;
+    let machine = Machine::from_scxml(text, "door.scxml", Span::call_site()).unwrap();
+
+    let states: Vec<String> = machine.states.iter().map(|state| state.state.to_string()).collect();
+    let variants: Vec<String> = machine.variants.iter().map(|variant| variant.state.to_string()).collect();
+    let events: Vec<String> = machine.events.iter().map(|event| event.name.to_string()).collect();
+
+    assert_eq!(states, ["Closed", "Opened"]);
+    assert_eq!(variants, ["Closed", "Opened"]);
+    assert_eq!(events, ["Close", "Open"]);
+}
+
+#[test]
+fn rejects_an_incomplete_event_order() {
+    let import = |events: &str| {
+        let text = format!{r#"<scxml xmlns="http://www.w3.org/2005/07/scxml" xmlns:fsm="https://github.com/lochers/static-fsm" version="1.0" name="door" initial="Closed" fsm:events="{}">
+            <state id="Closed"><transition event="Open" target="Opened"/></state>
+            <state id="Opened"><transition event="Close" target="Closed"/></state>
+        </scxml>"#, events};
+
+        Machine::from_scxml(&text, "door.scxml", Span::call_site()).err().expect("the document was accepted").to_string()
+    };
+
+    assert!(import("Open").contains("Event Close is missing from fsm:events"));
+    assert!(import("Open Close Open").contains("Duplicate event in fsm:events: Open"));
+    assert!(import("Open Close Slam").contains("Unknown event in fsm:events: Slam"));
+}
+
+#[test]
+fn export_then_import_keeps_the_snapshot_layout() {
+    round_trip("#[repr(u8)] #[version(2)] door {
+        Init[Closed]
+        Memory[Opened (u32, bool)]
+        Open: Closed => Opened
+        Close: Opened => Closed
+        Migrate[
+            1 { Idle => Closed, Opening (u16) => Opened, Opened (u8) },
+            #[repr(u8)]
+            0 { Closed, Opened (Option<u8>) }
+        ]
+    }");
+}
+
+#[test]
+fn writes_the_snapshot_layout_as_annotations() {
+    let machine = Machine::parse_str("#[repr(u8)] #[version(1)] door { Init[Closed] Open: Closed => Opened Close: Opened => Closed Migrate[0 { Idle => Closed, Opened (u8) }] }").unwrap();
+    let scxml = machine.scxml();
+
+    assert!(scxml.contains(r#" fsm:repr="u8" fsm:version="1">"#));
+    assert!(scxml.contains("  <fsm:migrate version=\"0\">\n    <fsm:state id=\"Idle\" target=\"Closed\"/>\n    <fsm:state id=\"Opened\" memory=\"u8\"/>\n  </fsm:migrate>\n"));
+}
+
+#[test]
+fn rejects_invalid_snapshot_annotations() {
+    let import = |attrs: &str, body: &str| {
+        let text = format!{r#"<scxml xmlns="http://www.w3.org/2005/07/scxml" xmlns:fsm="https://github.com/lochers/static-fsm" version="1.0" name="door" initial="Closed"{}>{}
+            <state id="Closed"><transition event="Open" target="Opened"/></state>
+            <state id="Opened"><transition event="Close" target="Closed"/></state>
+        </scxml>"#, attrs, body};
+
+        Machine::from_scxml(&text, "door.scxml", Span::call_site()).err().expect("the document was accepted").to_string()
+    };
+
+    assert!(import(r#" fsm:repr="u32""#, "").contains("Unsupported fsm:repr: u32, expected one of: u8, u16"));
+    assert!(import(r#" fsm:version="two""#, "").contains("Expected a version number, got: two"));
+    assert!(import(r#" fsm:version="1""#, r#"<fsm:migrate><fsm:state id="Closed"/></fsm:migrate>"#).contains("<fsm:migrate> without a `version` is not supported"));
+    assert!(import(r#" fsm:version="1""#, r#"<fsm:migrate version="0"><state id="Closed"/></fsm:migrate>"#).contains("<state> in <fsm:migrate> is not supported"));
+}
+
+#[test]
+fn export_then_import_keeps_the_delays() {
+    round_trip("link {
+        Init[Idle]
+        Connect: Idle => Connecting
+        Connected: Connecting => Online
+        Timeout after 500: Connecting, Online => Idle
+        Retry after 100: Idle => Connecting
+    }");
+
+    let machine = Machine::parse_str("link { Init[Idle] Connect: Idle => Connecting Timeout after 500: Connecting => Idle }").unwrap();
+    assert!(machine.scxml().contains(r#"<transition event="Timeout" target="Idle" fsm:after="500"/>"#));
+}
+
+#[test]
+fn rejects_invalid_delays() {
+    let import = |body: &str| {
+        let text = format!{r#"<scxml xmlns="http://www.w3.org/2005/07/scxml" xmlns:fsm="https://github.com/lochers/static-fsm" version="1.0" name="link" initial="Idle">{}</scxml>"#, body};
+        Machine::from_scxml(&text, "link.scxml", Span::call_site()).err().expect("the document was accepted").to_string()
+    };
+
+    assert!(import(r#"<state id="Idle"><transition event="Retry" target="Idle" fsm:after="soon"/></state>"#)
+        .contains("Expected a number of clock ticks for fsm:after, got: soon"));
+    assert!(import(r#"<state id="Idle"><transition event="Retry" target="Busy" fsm:after="5"/></state><state id="Busy"><transition event="Retry" target="Idle"/></state>"#)
+        .contains("Event Retry has no delay here, but fires after 5 from state Idle"));
+}
+
+#[test]
+fn export_then_import_round_trips_without_init() {
+    round_trip("stored {
+        Go: A => B
+        Back: B => A
+    }");
+}
+
+#[test]
+fn memory_data_has_its_own_id() {
+    let machine = Machine::parse_str("door { Init[Closed] Memory[Opened (u32)] Open: Closed => Opened Close: Opened => Closed }").unwrap();
+    let scxml = machine.scxml();
+
+    assert!(scxml.contains(r#"<data id="Opened.memory" fsm:memory="u32"/>"#));
+    assert!(scxml.contains(r#"<state id="Opened">"#));
+}
+
+#[test]
+fn omits_initial_without_init() {
+    let machine = Machine::parse_str("stored { Go: A => B Back: B => A }").unwrap();
+
+    assert!(!machine.scxml().contains("initial="));
+    assert!(machine.scxml().contains(r#"fsm:init="false""#));
+}
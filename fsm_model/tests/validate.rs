@@ -0,0 +1,56 @@
+use fsm_model::Machine;
+
+fn errors(src: &str) -> Vec<String> {
+    match Machine::parse_str(src).unwrap().validate() {
+        Ok (()) => Vec::new(),
+        Err (err) => err.into_iter().map(|err| err.to_string()).collect()
+    }
+}
+
+#[test]
+fn rejects_unreachable_states() {
+    assert_eq!(errors("door { Init[Closed] Final[Broken] Open: Closed => Opened Close: Opened => Closed Kick: Closed, Locked => Broken Unlock: Locked => Closed }"), [
+        "State Locked is unreachable from the Init states\n  = help: allow with #[allow(unreachable_states)]"
+    ]);
+}
+
+#[test]
+fn allows_unreachable_states() {
+    assert!(errors("#[allow(unreachable_states)] door { Init[Closed] Final[Broken] Open: Closed => Opened Close: Opened => Closed Kick: Closed, Locked => Broken }").is_empty());
+}
+
+#[test]
+fn rejects_dead_ends_with_their_path() {
+    assert_eq!(errors("door { Init[Closed] Open: Closed => Opened Close: Opened => Closed Kick: Opened => Broken }"), [
+        "State Broken is a dead end: it has no outgoing transitions and is not declared Final, reached by: Closed --Open--> Opened --Kick--> Broken\n  = help: allow with #[allow(dead_end_states)]"
+    ]);
+}
+
+#[test]
+fn accepts_final_and_allowed_dead_ends() {
+    assert!(errors("door { Init[Closed] Final[Broken] Open: Closed => Opened Close: Opened => Closed Kick: Opened => Broken }").is_empty());
+    assert!(errors("#[allow(dead_end_states)] door { Init[Closed] Open: Closed => Opened Close: Opened => Closed Kick: Opened => Broken }").is_empty());
+}
+
+#[test]
+fn rejects_transitions_out_of_final_states() {
+    assert_eq!(errors("door { Init[Closed] Final[Broken] Kick: Closed => Broken Fix: Broken => Closed }"), [
+        "Final state cannot have outgoing transitions: Broken"
+    ]);
+}
+
+#[test]
+fn rejects_references_to_undeclared_states() {
+    assert_eq!(errors("door { Init[Closed] Memory[Opend (u32)] Trace[Closd] Final[Brokn] Open: Closed => Opened Close: Opened => Closed }"), [
+        "State Opend in the Memory block is not used in Init or any transition; did you mean `Opened`?",
+        "State Closd in the Trace block is not used in Init or any transition; did you mean `Closed`?",
+        "State Brokn in the Final block is not used in Init or any transition"
+    ]);
+}
+
+#[test]
+fn rejects_machines_without_states() {
+    assert_eq!(errors("empty { }"), [
+        "Machine empty has no states\n  = help: add at least one event, e.g. `Start: Idle => Running`"
+    ]);
+}